@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use cuteopt::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+enum BenchState {
+    Verbose,
+    Output,
+    Include,
+    #[default]
+    Unknow,
+}
+
+fn ctx(slash_alias: bool) -> Ctx<'static, BenchState> {
+    let mut ctx = Ctx::new();
+
+    ctx.set_slash_alias(slash_alias);
+    ctx.add_flag("-v", "--verbose", BenchState::Verbose)
+        .add_arg("-o", "--output", BenchState::Output)
+        .add_str("-I", BenchState::Include)
+        .multiple(BenchState::Include, true);
+    for index in 0..256 {
+        ctx.add_bool(format!("--flag-{}", index), BenchState::Unknow);
+    }
+    ctx
+}
+
+fn args(count: usize) -> Vec<String> {
+    let mut args = vec![];
+
+    for index in 0..count / 4 {
+        args.push(String::from("-I"));
+        args.push(format!("include/{}", index % 64));
+        args.push(format!("--output=out_{}", index % 16));
+        args.push(format!("file_{}.c", index));
+    }
+    args
+}
+
+fn parse_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    for (count, slash_alias) in [(10_000, false), (100_000, false), (100_000, true)] {
+        let args = args(count);
+        let name = if slash_alias {
+            format!("{}_args_slash_alias", count)
+        } else {
+            format!("{}_args", count)
+        };
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || (ctx(slash_alias), args.clone()),
+                // return the ctx so it is not dropped in the measurement
+                |(mut ctx, args)| {
+                    let ret = ctx.parse(&mut args.into_iter());
+
+                    black_box((ctx, ret))
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,202 @@
+//! Derive macros of [`cuteopt`](https://araraloren.github.io/cuteopt/),
+//! use them through the `derive` feature of `cuteopt`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, LitStr, Path};
+
+/// Generate `fn build() -> cuteopt::Ctx<'static, Self>` for a state enum
+/// from the `#[cute(...)]` attributes of its variants.
+///
+/// The supported keys are `switch`, `option`, `cmd` for the option kind and name,
+/// `alias` (repeatable) and `help`.
+#[proc_macro_derive(StateOpt, attributes(cute))]
+pub fn derive_state_opt(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    state_opt(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generate `TryFrom<&cuteopt::Ctx<S>>` for a struct with named fields.
+///
+/// The struct need `#[cute(state = S)]` naming the state type,
+/// every field with `#[cute(state = S::Variant)]` is parsed by `Ctx::get_value_as`,
+/// the other fields are set to `Default::default()`.
+#[proc_macro_derive(FromCute, attributes(cute))]
+pub fn derive_from_cute(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_cute(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn from_cute(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "FromCute can only be derived for struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "FromCute can only be derived for struct",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "FromCute does not support generic struct",
+        ));
+    }
+    let mut state_type: Option<Path> = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cute"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("state") {
+                state_type = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown cute attribute"))
+            }
+        })?;
+    }
+    let state_type = state_type.ok_or_else(|| {
+        syn::Error::new_spanned(name, "missing #[cute(state = StateType)] on struct")
+    })?;
+    let mut inits = vec![];
+
+    for field in fields.iter() {
+        let ident = &field.ident;
+        let mut state: Option<Expr> = None;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cute"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("state") {
+                    state = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown cute attribute"))
+                }
+            })?;
+        }
+        inits.push(match state {
+            Some(state) => quote!(#ident: ctx.get_value_as(#state)?),
+            None => quote!(#ident: ::std::default::Default::default()),
+        });
+    }
+
+    Ok(quote! {
+        impl<'c, 'a> ::std::convert::TryFrom<&'c ::cuteopt::Ctx<'a, #state_type>> for #name {
+            type Error = ::cuteopt::Error;
+
+            fn try_from(
+                ctx: &'c ::cuteopt::Ctx<'a, #state_type>,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                ::std::result::Result::Ok(#name {
+                    #(#inits,)*
+                })
+            }
+        }
+    })
+}
+
+fn state_opt(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "StateOpt can only be derived for enum",
+            ))
+        }
+    };
+    let mut stmts = vec![];
+
+    for variant in data.variants.iter() {
+        let ident = &variant.ident;
+        let mut add = None;
+        let mut extra = vec![];
+
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cute"))
+        {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "#[cute] can only be used on unit variant",
+                ));
+            }
+            attr.parse_nested_meta(|meta| {
+                let value: LitStr = meta.value()?.parse()?;
+                let method = if meta.path.is_ident("switch") {
+                    quote!(add_bool)
+                } else if meta.path.is_ident("option") {
+                    quote!(add_str)
+                } else if meta.path.is_ident("cmd") {
+                    quote!(add_cmd)
+                } else if meta.path.is_ident("alias") {
+                    extra.push(quote!(ctx.alias(#name::#ident, #value);));
+                    return Ok(());
+                } else if meta.path.is_ident("help") {
+                    extra.push(quote!(ctx.help(#name::#ident, #value);));
+                    return Ok(());
+                } else {
+                    return Err(meta.error("unknown cute attribute"));
+                };
+
+                if add.is_some() {
+                    return Err(meta.error("option kind already set"));
+                }
+                add = Some(quote!(ctx.#method(#value, #name::#ident);));
+                Ok(())
+            })?;
+        }
+        match add {
+            Some(add) => {
+                stmts.push(add);
+                stmts.extend(extra);
+            }
+            None if !extra.is_empty() => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "missing `switch`, `option` or `cmd` in #[cute]",
+                ));
+            }
+            None => {}
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn build() -> ::cuteopt::Ctx<'static, Self> {
+                let mut ctx = ::cuteopt::Ctx::new();
+
+                #(#stmts)*
+                ctx
+            }
+        }
+    })
+}
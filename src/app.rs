@@ -0,0 +1,239 @@
+//! Run an application of sub commands, each command has a handler.
+//!
+//! ```no_run
+//! use cuteopt::prelude::*;
+//! use cuteopt::app::App;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Build,
+//!     Clean,
+//!     Verbose,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut app = App::new("app", Ctx::new());
+//!
+//! app.ctx_mut().add_flag("-v", "--verbose", ParseState::Verbose);
+//! app.command("build", ParseState::Build, |ctx| {
+//!     if ctx.get_value_as_bool(ParseState::Verbose) {
+//!         println!("building");
+//!     }
+//!     Ok(())
+//! })
+//! .command("clean", ParseState::Clean, |_| Err(Error::new("nothing to clean")));
+//!
+//! std::process::exit(app.run_env());
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::io::Write;
+
+use super::{help, Ctx, Error, Outcome};
+
+/// The exit code when the handler succeed
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// The exit code when the handler failed
+pub const EXIT_FAILURE: i32 = 1;
+
+/// The exit code when the command line is invalid
+pub const EXIT_USAGE: i32 = 2;
+
+type Handler<'a, S> = Box<dyn Fn(&Ctx<'a, S>) -> Result<(), Error>>;
+
+/// [`App`] parse the arguments, dispatch to the handler of matched command and
+/// return the exit code
+pub struct App<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    program: String,
+    ctx: Ctx<'a, S>,
+    handlers: Vec<(S, Handler<'a, S>)>,
+    fallback: Option<Handler<'a, S>>,
+}
+
+impl<'a, S> App<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    pub fn new(program: impl Into<String>, ctx: Ctx<'a, S>) -> Self {
+        App {
+            program: program.into(),
+            ctx,
+            handlers: vec![],
+            fallback: None,
+        }
+    }
+
+    pub fn ctx(&self) -> &Ctx<'a, S> {
+        &self.ctx
+    }
+
+    pub fn ctx_mut(&mut self) -> &mut Ctx<'a, S> {
+        &mut self.ctx
+    }
+
+    /// Add the command `name` bound to state `s` with its handler
+    pub fn command(
+        &mut self,
+        name: &'a str,
+        s: S,
+        handler: impl Fn(&Ctx<'a, S>) -> Result<(), Error> + 'static,
+    ) -> &mut Self {
+        self.ctx.add_cmd(name, s.clone());
+        self.handle(s, handler)
+    }
+
+    /// Set the handler of state `s`, such as an [`exclusive`](Ctx::exclusive) `--version`
+    pub fn handle(
+        &mut self,
+        s: S,
+        handler: impl Fn(&Ctx<'a, S>) -> Result<(), Error> + 'static,
+    ) -> &mut Self {
+        self.handlers.retain(|(exist, _)| *exist != s);
+        self.handlers.push((s, Box::new(handler)));
+        self
+    }
+
+    /// Set the handler called when no command matched
+    pub fn fallback(
+        &mut self,
+        handler: impl Fn(&Ctx<'a, S>) -> Result<(), Error> + 'static,
+    ) -> &mut Self {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Same as [`run`](App::run) with the arguments of process
+    pub fn run_env(&mut self) -> i32 {
+        self.run(std::env::args().skip(1))
+    }
+
+    /// Parse `args` and call the handler, the errors are printed to stderr
+    pub fn run(&mut self, args: impl IntoIterator<Item = String>) -> i32 {
+        self._run(args, &mut std::io::stderr())
+    }
+
+    fn _run(&mut self, args: impl IntoIterator<Item = String>, err: &mut dyn Write) -> i32 {
+        let matched = match self.ctx.parse_outcome(&mut args.into_iter()) {
+            Ok(Outcome::Exit(s)) => Some(s),
+            Ok(Outcome::Continue(_)) => self
+                .handlers
+                .iter()
+                .map(|(s, _)| s)
+                .find(|s| self.ctx.get_value_as_bool((*s).clone()))
+                .cloned(),
+            Err(e) => {
+                let _ = writeln!(
+                    err,
+                    "error: {}\n\n{}",
+                    e,
+                    help::usage(&self.ctx, &self.program)
+                );
+                return EXIT_USAGE;
+            }
+        };
+        let handler = match matched {
+            Some(s) => self
+                .handlers
+                .iter()
+                .find(|(exist, _)| *exist == s)
+                .map(|(_, handler)| handler),
+            None => self.fallback.as_ref(),
+        };
+        let handler = match handler {
+            Some(handler) => handler,
+            None => {
+                let _ = write!(
+                    err,
+                    "error: missing command\n\n{}",
+                    help::help(&self.ctx, &self.program)
+                );
+                return EXIT_USAGE;
+            }
+        };
+
+        match handler(&self.ctx) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(e) => {
+                let _ = writeln!(err, "error: {}", e);
+                EXIT_FAILURE
+            }
+        }
+    }
+}
+
+impl<'a, S> Debug for App<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("App")
+            .field("program", &self.program)
+            .field("ctx", &self.ctx)
+            .field(
+                "handlers",
+                &self.handlers.iter().map(|(s, _)| s).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn app_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Build,
+            Clean,
+            Version,
+            Jobs,
+            #[default]
+            Unknow,
+        }
+
+        let mut app = App::new("app", Ctx::new());
+
+        app.ctx_mut()
+            .add_str("--jobs", TestState::Jobs)
+            .add_bool("--version", TestState::Version)
+            .exclusive(TestState::Version, true)
+            .set_parse_policy(crate::ParsePolicy::Fresh);
+        app.command("build", TestState::Build, |ctx| {
+            ctx.get_value_as::<u32>(TestState::Jobs).map(|_| ())
+        })
+        .command("clean", TestState::Clean, |_| {
+            Err(Error::new("nothing to clean"))
+        })
+        .handle(TestState::Version, |_| Ok(()));
+
+        let mut run = |args: &[&str]| {
+            let mut err = vec![];
+            let code = app._run(args.iter().map(|data| String::from(*data)), &mut err);
+
+            (code, String::from_utf8(err).unwrap())
+        };
+
+        assert_eq!(
+            run(&["build", "--jobs", "4"]),
+            (EXIT_SUCCESS, String::new())
+        );
+        assert_eq!(run(&["--version"]), (EXIT_SUCCESS, String::new()));
+        assert_eq!(
+            run(&["clean"]),
+            (EXIT_FAILURE, String::from("error: nothing to clean\n"))
+        );
+        assert_eq!(run(&["build", "--jobs", "x"]).0, EXIT_FAILURE);
+        assert_eq!(run(&["--version", "build"]).0, EXIT_SUCCESS);
+        assert_eq!(run(&["build", "--jobs"]).0, EXIT_USAGE);
+        assert!(run(&[])
+            .1
+            .starts_with("error: missing command\n\nUsage: app"));
+    }
+}
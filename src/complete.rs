@@ -0,0 +1,290 @@
+//! Generate shell completion script from the [`Ctx`].
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::complete::{self, ValueHint};
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     Output,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_flag("-v", "--verbose", ParseState::Verbose)
+//!     .add_arg("-o", "--output", ParseState::Output)
+//!     .hint(ParseState::Output, ValueHint::FilePath);
+//!
+//! let script = complete::bash(&ctx, "app");
+//!
+//! assert!(script.contains("complete -F _app app"));
+//! assert!(script.contains("compgen -f"));
+//! ```
+//!
+//! The candidates can also be produced at completion time by a [`Completer`],
+//! the [`Completer::Callback`] is called by the program itself, check [`dynamic`] at the start of `main`:
+//!
+//! ```no_run
+//! use cuteopt::prelude::*;
+//! use cuteopt::complete::{self, Completer};
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Branch,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_str("--branch", ParseState::Branch).completer(
+//!     ParseState::Branch,
+//!     Completer::callback(|_| vec![String::from("main"), String::from("dev")]),
+//! );
+//!
+//! if let Some(candidates) = complete::dynamic(&ctx) {
+//!     for candidate in candidates {
+//!         println!("{}", candidate);
+//!     }
+//!     return;
+//! }
+//! ```
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use super::Ctx;
+
+/// The environment variable hold the option name when the program is called for completion
+pub const COMPLETE_ENV: &str = "CUTEOPT_COMPLETE";
+
+/// The environment variable hold the current word when the program is called for completion
+pub const CURRENT_ENV: &str = "CUTEOPT_CURRENT";
+
+/// [`ValueHint`] tell the shell what kind of value the option accept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueHint {
+    #[default]
+    Unknown,
+    FilePath,
+    DirPath,
+    Hostname,
+    Command,
+}
+
+impl ValueHint {
+    /// Return the `compgen` action of bash
+    pub fn bash_action(&self) -> Option<&'static str> {
+        match self {
+            ValueHint::Unknown => None,
+            ValueHint::FilePath => Some("-f"),
+            ValueHint::DirPath => Some("-d"),
+            ValueHint::Hostname => Some("-A hostname"),
+            ValueHint::Command => Some("-c"),
+        }
+    }
+}
+
+type CompleterFn = dyn Fn(&str) -> Vec<String> + Send + Sync;
+
+/// [`Completer`] produce the candidate values of option at completion time
+#[derive(Clone)]
+pub enum Completer {
+    /// Shell command printing the candidates, such as `git branch --format='%(refname:short)'`
+    Command(String),
+    /// Closure called with the current word, through the protocol of [`dynamic`]
+    Callback(Arc<CompleterFn>),
+}
+
+impl Completer {
+    pub fn command(command: impl Into<String>) -> Self {
+        Completer::Command(command.into())
+    }
+
+    pub fn callback(callback: impl Fn(&str) -> Vec<String> + Send + Sync + 'static) -> Self {
+        Completer::Callback(Arc::new(callback))
+    }
+}
+
+impl std::fmt::Debug for Completer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Completer::Command(command) => f.debug_tuple("Command").field(command).finish(),
+            Completer::Callback(_) => f.debug_tuple("Callback").finish(),
+        }
+    }
+}
+
+/// Return the candidates of the [`Completer::Callback`] of option `name` starting with `current`
+pub fn candidates<S>(ctx: &Ctx<'_, S>, name: &str, current: &str) -> Option<Vec<String>>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let opt_keeper = ctx
+        .active()
+        .find(|ok| ok.is_named(name) || ctx.auto_short_of(ok.state()) == Some(name))?;
+
+    match &opt_keeper.completer {
+        Some(Completer::Callback(callback)) => Some(
+            callback(current)
+                .into_iter()
+                .filter(|candidate| candidate.starts_with(current))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Return the candidates if the program is called by the completion script,
+/// the option name and current word are read from [`COMPLETE_ENV`] and [`CURRENT_ENV`]
+pub fn dynamic<S>(ctx: &Ctx<'_, S>) -> Option<Vec<String>>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let name = std::env::var(COMPLETE_ENV).ok()?;
+    let current = std::env::var(CURRENT_ENV).unwrap_or_default();
+
+    Some(candidates(ctx, &name, &current).unwrap_or_default())
+}
+
+/// Generate the bash completion script of `program`
+pub fn bash<S>(ctx: &Ctx<'_, S>, program: &str) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let function: String = program
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let mut words = vec![];
+    let mut cases = String::new();
+
+    for opt_keeper in ctx.active().filter(|ok| !ok.opt.is_pattern()) {
+        let names: Vec<&str> = opt_keeper
+            .names()
+            .chain(ctx.auto_short_of(opt_keeper.state()))
+            .collect();
+
+        if let Some(completer) = &opt_keeper.completer {
+            let reply = match completer {
+                Completer::Command(command) => {
+                    format!("$(compgen -W \"$({})\" -- \"$cur\")", command)
+                }
+                Completer::Callback(_) => format!(
+                    "$({}=\"$prev\" {}=\"$cur\" \"${{COMP_WORDS[0]}}\" 2>/dev/null)",
+                    COMPLETE_ENV, CURRENT_ENV
+                ),
+            };
+            let _ = write!(
+                cases,
+                "        {})\n            COMPREPLY=({})\n            return 0\n            ;;\n",
+                names.join("|"),
+                reply
+            );
+        } else if let Some(action) = opt_keeper.hint.bash_action() {
+            let _ = write!(
+                cases,
+                "        {})\n            COMPREPLY=($(compgen {} -- \"$cur\"))\n            return 0\n            ;;\n",
+                names.join("|"),
+                action
+            );
+        }
+        words.extend(names);
+    }
+    let mut script = String::new();
+
+    let _ = writeln!(script, "_{}() {{", function);
+    let _ = writeln!(script, "    local cur prev");
+    let _ = writeln!(script, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+    let _ = writeln!(script, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
+    if !cases.is_empty() {
+        let _ = writeln!(script, "    case \"$prev\" in");
+        script.push_str(&cases);
+        let _ = writeln!(script, "    esac");
+    }
+    let _ = writeln!(
+        script,
+        "    COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        words.join(" ")
+    );
+    let _ = writeln!(script, "}}");
+    let _ = writeln!(script, "complete -F _{} {}", function, program);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn completer_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Branch,
+            Remote,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--branch", TestState::Branch)
+            .add_str("--remote", TestState::Remote)
+            .completer(
+                TestState::Branch,
+                Completer::callback(|_| vec![String::from("main"), String::from("dev")]),
+            )
+            .completer(TestState::Remote, Completer::command("git remote"));
+
+        let script = bash(&ctx, "app");
+
+        assert!(script.contains("compgen -W \"$(git remote)\" -- \"$cur\""));
+        assert!(script.contains("CUTEOPT_COMPLETE=\"$prev\" CUTEOPT_CURRENT=\"$cur\""));
+        assert_eq!(
+            candidates(&ctx, "--branch", "m"),
+            Some(vec![String::from("main")])
+        );
+        assert_eq!(candidates(&ctx, "--remote", ""), None);
+    }
+
+    #[test]
+    fn bash_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_flag("-v", "--verbose", TestState::Verbose)
+            .add_str("--output", TestState::Output)
+            .hint(TestState::Output, ValueHint::FilePath);
+
+        assert_eq!(
+            bash(&ctx, "my-app"),
+            concat!(
+                "_my_app() {\n",
+                "    local cur prev\n",
+                "    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n",
+                "    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n",
+                "    case \"$prev\" in\n",
+                "        --output)\n",
+                "            COMPREPLY=($(compgen -f -- \"$cur\"))\n",
+                "            return 0\n",
+                "            ;;\n",
+                "    esac\n",
+                "    COMPREPLY=($(compgen -W \"--verbose -v --output\" -- \"$cur\"))\n",
+                "}\n",
+                "complete -F _my_app my-app\n",
+            )
+        );
+    }
+}
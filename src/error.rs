@@ -0,0 +1,140 @@
+//! The error type returned by the parse and value APIs of [`Ctx`](crate::Ctx).
+
+use std::fmt::{Display, Formatter};
+
+/// [`Error`] is the error produced by parsing, the variant tell the kind of error
+/// and hold the message, it implement [`std::error::Error`] so it work with `?`
+/// and the boxed errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The value is invalid, such as the [`ValueParser`](crate::val::ValueParser) failed
+    Value(String),
+    /// The value of option or the required argument is missing
+    Missing(String),
+    /// The argument looks like an option but no option matched it
+    UnknownOption(String),
+    /// The argument is not accepted where it appear, such as a value of switch
+    Argument(String),
+    /// The arguments exceed the [`Limits`](crate::Limits)
+    Limit(String),
+    /// The option definitions are invalid, such as the conflicting names
+    Config(String),
+    /// Other errors, such as the errors of application
+    Other(String),
+}
+
+impl Error {
+    /// Create an [`Error::Other`] with `message`
+    pub fn new(message: impl Into<String>) -> Self {
+        Error::Other(message.into())
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Value(message)
+            | Error::Missing(message)
+            | Error::UnknownOption(message)
+            | Error::Argument(message)
+            | Error::Limit(message)
+            | Error::Config(message)
+            | Error::Other(message) => message,
+        }
+    }
+
+    pub fn into_message(self) -> String {
+        match self {
+            Error::Value(message)
+            | Error::Missing(message)
+            | Error::UnknownOption(message)
+            | Error::Argument(message)
+            | Error::Limit(message)
+            | Error::Config(message)
+            | Error::Other(message) => message,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::new(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::new(message)
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.into_message()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn error_test() {
+        use super::*;
+        use crate::{Ctx, Limits, Unmatched};
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Jobs,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_flag("-v", "--verbose", TestState::Verbose)
+            .add_str("--jobs", TestState::Jobs)
+            .set_limits(Limits {
+                max_value_len: Some(4),
+                ..Limits::default()
+            });
+
+        let mut parse = |args: &[&str]| ctx.parse(&mut args.iter().map(|data| String::from(*data)));
+
+        assert_eq!(
+            parse(&["--jobs"]),
+            Err(Error::Missing(String::from(
+                "Option need argument: Opt(\"--jobs\", Jobs)"
+            )))
+        );
+        assert!(matches!(parse(&["--verbose=1"]), Err(Error::Argument(_))));
+        assert!(matches!(parse(&["--jobs", "12345"]), Err(Error::Limit(_))));
+        assert!(parse(&["-v", "--jobs", "x"]).is_ok());
+        assert!(matches!(
+            ctx.get_value_as::<usize>(TestState::Jobs),
+            Err(Error::Value(_))
+        ));
+        assert_eq!(
+            ctx.get_value_as::<usize>(TestState::Verbose)
+                .map_err(|e| e.message().contains("boolean")),
+            Err(true)
+        );
+        assert!(matches!(
+            Ctx::<TestState>::new().get_value_as::<usize>(TestState::Jobs),
+            Err(Error::Missing(_))
+        ));
+        assert_eq!(
+            Unmatched::LooksLikeOption(String::from("--verbos")).into_positional(),
+            Err(Error::UnknownOption(String::from(
+                "Unknown option `--verbos`"
+            )))
+        );
+        assert_eq!(Error::new("failed").to_string(), "failed");
+        assert_eq!(String::from(Error::Value(String::from("x"))), "x");
+    }
+}
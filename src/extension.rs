@@ -0,0 +1,108 @@
+//! Store data of any type in the [`Ctx`](super::Ctx), one value for each type.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Source(&'static str);
+//!
+//! let mut ctx: Ctx<ParseState> = Ctx::new();
+//!
+//! ctx.extensions_mut().insert(Source("cli"));
+//!
+//! assert_eq!(ctx.extensions().get::<Source>(), Some(&Source("cli")));
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// [`Extensions`] is a map from type to the value of that type
+#[derive(Debug, Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Extensions::default()
+    }
+
+    /// Insert `value`, return the previous value of the same type
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|prev| prev.downcast().ok())
+            .map(|prev| *prev)
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+    }
+
+    /// Return the value of type `T`, insert the one created by `f` if not exist
+    pub fn get_or_insert_with<T: Any + Send + Sync>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(f()))
+            .downcast_mut()
+            .expect("the value must have the type of its key")
+    }
+
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    pub fn contains<T: Any>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn extensions_test() {
+        use super::*;
+
+        let mut extensions = Extensions::new();
+
+        assert_eq!(extensions.insert(1_u32), None);
+        assert_eq!(extensions.insert(2_u32), Some(1));
+        assert_eq!(extensions.insert(String::from("a")), None);
+        *extensions.get_or_insert_with(|| 0_usize) += 5;
+        *extensions.get_or_insert_with(|| 0_usize) += 5;
+        assert_eq!(extensions.get::<usize>(), Some(&10));
+        assert_eq!(extensions.get::<u32>(), Some(&2));
+        assert_eq!(extensions.len(), 3);
+        assert_eq!(extensions.remove::<String>(), Some(String::from("a")));
+        assert!(!extensions.contains::<String>());
+    }
+}
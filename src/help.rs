@@ -0,0 +1,417 @@
+//! Generate the usage and help message from the [`Ctx`].
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::help;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     Output,
+//!     Inputs,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_flag("-v", "--verbose", ParseState::Verbose)
+//!     .help(ParseState::Verbose, "Print more information")
+//!     .add_arg("-o", "--output", ParseState::Output)
+//!     .value_name(ParseState::Output, "FILE")
+//!     .positional_rest(ParseState::Inputs, 1)
+//!     .value_name(ParseState::Inputs, "INPUT");
+//!
+//! assert_eq!(help::usage(&ctx, "app"), "Usage: app [OPTIONS] <INPUT>...");
+//! assert!(help::help(&ctx, "app").contains("-o, --output <FILE>"));
+//! ```
+
+use std::fmt::Write;
+
+use super::{Ctx, OptKeeper};
+
+const DEFAULT_VALUE_NAME: &str = "VALUE";
+
+const DEFAULT_REST_NAME: &str = "ARGS";
+
+/// Generate the usage line such as `Usage: app [OPTIONS] <INPUT>...`
+pub fn usage<S>(ctx: &Ctx<'_, S>, program: &str) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut usage = format!("Usage: {}", program);
+
+    if ctx.active().any(|ok| !ok.opt.is_cmd()) {
+        usage.push_str(" [OPTIONS]");
+    }
+    if ctx.active().any(|ok| ok.opt.is_cmd()) {
+        usage.push_str(" <COMMAND>");
+    }
+    if let Some((state, _, at_least)) = &ctx.positional_rest {
+        let name = ctx.positional_name(state).unwrap_or(DEFAULT_REST_NAME);
+
+        if *at_least > 0 {
+            let _ = write!(usage, " <{}>...", name);
+        } else {
+            let _ = write!(usage, " [<{}>...]", name);
+        }
+    }
+    if let Some((state, _)) = &ctx.trailing {
+        let name = ctx.positional_name(state).unwrap_or(DEFAULT_REST_NAME);
+
+        let _ = write!(usage, " [-- <{}>...]", name);
+    }
+    usage
+}
+
+/// Generate the help message, include the before help, usage line, commands, positionals,
+/// options, examples and the after help
+pub fn help<S>(ctx: &Ctx<'_, S>, program: &str) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut help = String::new();
+
+    if !ctx.before_help.is_empty() {
+        let _ = write!(help, "{}\n\n", ctx.before_help);
+    }
+    help.push_str(&usage(ctx, program));
+    for section in [
+        commands(ctx),
+        positionals(ctx),
+        options(ctx),
+        examples(ctx),
+        ctx.after_help.to_string(),
+    ]
+    .iter()
+    {
+        if !section.is_empty() {
+            let _ = write!(help, "\n\n{}", section);
+        }
+    }
+    help.push('\n');
+    help
+}
+
+/// Generate the help message from `template`, the placeholders are
+/// `{before-help}`, `{usage}`, `{commands}`, `{options}`, `{positionals}`, `{examples}` and `{after-help}`,
+/// the placeholder of an empty section is replaced with empty string
+pub fn help_with<S>(ctx: &Ctx<'_, S>, program: &str, template: &str) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut help = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let (text, placeholder) = rest.split_at(start);
+        let end = placeholder
+            .find('}')
+            .map_or(placeholder.len(), |end| end + 1);
+        let section = match &placeholder[..end] {
+            "{before-help}" => ctx.before_help.to_string(),
+            "{usage}" => usage(ctx, program),
+            "{commands}" => commands(ctx),
+            "{options}" => options(ctx),
+            "{positionals}" => positionals(ctx),
+            "{examples}" => examples(ctx),
+            "{after-help}" => ctx.after_help.to_string(),
+            placeholder => String::from(placeholder),
+        };
+
+        help.push_str(text);
+        help.push_str(&section);
+        rest = &placeholder[end..];
+    }
+    help.push_str(rest);
+    help
+}
+
+/// Generate the `Commands:` section
+pub fn commands<S>(ctx: &Ctx<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let items: Vec<(String, &str)> = ctx
+        .active()
+        .filter(|ok| ok.opt.is_cmd())
+        .map(|ok| {
+            (
+                ok.names().collect::<Vec<&str>>().join(", "),
+                ok.help.as_ref(),
+            )
+        })
+        .collect();
+
+    section("Commands", &items)
+}
+
+/// Generate the `Options:` section
+pub fn options<S>(ctx: &Ctx<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let items: Vec<(String, String)> = ctx
+        .active()
+        .filter(|ok| !ok.opt.is_cmd())
+        .map(|ok| match ctx.auto_short_of(ok.state()) {
+            Some(short) => (format!("{}, {}", short, option_spec(ok)), option_help(ok)),
+            None => (option_spec(ok), option_help(ok)),
+        })
+        .collect();
+    section("Options", &items)
+}
+
+/// Generate the `Arguments:` section of the positional and trailing arguments
+pub fn positionals<S>(ctx: &Ctx<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut items = vec![];
+
+    if let Some((state, _, _)) = &ctx.positional_rest {
+        let name = ctx.positional_name(state).unwrap_or(DEFAULT_REST_NAME);
+
+        items.push((format!("<{}>...", name), ""));
+    }
+    if let Some((state, _)) = &ctx.trailing {
+        let name = ctx.positional_name(state).unwrap_or(DEFAULT_REST_NAME);
+
+        items.push((format!("-- <{}>...", name), ""));
+    }
+    section("Arguments", &items)
+}
+
+/// Generate the `Examples:` section
+pub fn examples<S>(ctx: &Ctx<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let items: Vec<(String, &str)> = ctx
+        .examples
+        .iter()
+        .map(|(command, description)| (command.to_string(), description.as_ref()))
+        .collect();
+
+    section("Examples", &items)
+}
+
+fn section<T: AsRef<str>>(title: &str, items: &[(String, T)]) -> String {
+    let mut section = String::new();
+
+    if items.is_empty() {
+        return section;
+    }
+    let width = items.iter().map(|(spec, _)| spec.len()).max().unwrap_or(0);
+
+    let _ = write!(section, "{}:", title);
+    for (spec, text) in items.iter() {
+        let text = text.as_ref();
+
+        if text.is_empty() {
+            let _ = write!(section, "\n  {}", spec);
+        } else {
+            let _ = write!(section, "\n  {:width$}  {}", spec, text, width = width);
+        }
+    }
+    section
+}
+
+/// Return the help of option followed by `[env: NAME]` and `[default: VALUE]`
+pub fn option_help<S>(opt_keeper: &OptKeeper<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut help = String::from(opt_keeper.help.as_ref());
+
+    if let Some(env) = &opt_keeper.env {
+        let _ = write!(help, " [env: {}]", env);
+    }
+    match &opt_keeper.default_value {
+        Some(default) if !opt_keeper.hide_default => {
+            let _ = write!(help, " [default: {}]", default);
+        }
+        _ => {}
+    }
+    String::from(help.trim_start())
+}
+
+/// Return the names of option with the value placeholder, such as `-o, --output <FILE>`,
+/// the shorter names come first
+pub fn option_spec<S>(opt_keeper: &OptKeeper<'_, S>) -> String
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let mut names: Vec<&str> = opt_keeper.names().collect();
+
+    names.sort_by_key(|name| name.len());
+
+    let mut spec = names.join(", ");
+    let value_name = if opt_keeper.value_name.is_empty() {
+        DEFAULT_VALUE_NAME
+    } else {
+        opt_keeper.value_name.as_ref()
+    };
+
+    if opt_keeper.opt.is_raw() {
+        let _ = write!(spec, " <{}>... {}", value_name, opt_keeper.terminator);
+    } else if !opt_keeper.opt.is_switch() && !opt_keeper.opt.is_pattern() {
+        if opt_keeper.require_equals {
+            let _ = write!(spec, "=<{}>", value_name);
+        } else {
+            let _ = write!(spec, " <{}>", value_name);
+        }
+    }
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn help_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Build,
+            Jobs,
+            Quiet,
+            Files,
+            Args,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_cmd("build", TestState::Build)
+            .help(TestState::Build, "Build the project")
+            .add_str("--jobs", TestState::Jobs)
+            .require_equals(TestState::Jobs, true)
+            .value_name(TestState::Jobs, "N")
+            .help(TestState::Jobs, "Number of jobs")
+            .add_flag("-q", "--quiet", TestState::Quiet)
+            .positional_rest(TestState::Files, 0)
+            .value_name(TestState::Files, "FILES")
+            .trailing(TestState::Args);
+
+        assert_eq!(
+            usage(&ctx, "app"),
+            "Usage: app [OPTIONS] <COMMAND> [<FILES>...] [-- <ARGS>...]"
+        );
+        assert_eq!(
+            help(&ctx, "app"),
+            "Usage: app [OPTIONS] <COMMAND> [<FILES>...] [-- <ARGS>...]\n\
+             \n\
+             Commands:\n  build  Build the project\n\
+             \n\
+             Arguments:\n  <FILES>...\n  -- <ARGS>...\n\
+             \n\
+             Options:\n  --jobs=<N>   Number of jobs\n  -q, --quiet\n"
+        );
+    }
+
+    #[test]
+    fn help_with_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            Files,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_arg("-o", "--output", TestState::Output)
+            .help(TestState::Output, "Output file")
+            .positional_rest(TestState::Files, 1)
+            .value_name(TestState::Files, "FILE")
+            .after_help("See the manual for more.");
+
+        assert_eq!(
+            help_with(
+                &ctx,
+                "app",
+                "{usage}\n\n{positionals}\n\n{options}{commands}\n\n{after-help} {unknown}\n"
+            ),
+            "Usage: app [OPTIONS] <FILE>...\n\
+             \n\
+             Arguments:\n  <FILE>...\n\
+             \n\
+             Options:\n  -o, --output <VALUE>  Output file\n\
+             \n\
+             See the manual for more. {unknown}\n"
+        );
+        assert!(help(&ctx, "app").ends_with("Output file\n\nSee the manual for more.\n"));
+    }
+
+    #[test]
+    fn examples_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_arg("-o", "--output", TestState::Output)
+            .before_help("app 1.0")
+            .example("app -o out in.txt", "basic usage")
+            .example("app in.txt", "print to stdout")
+            .after_help("Report bugs to the issue tracker.");
+
+        assert_eq!(
+            help(&ctx, "app"),
+            "app 1.0\n\
+             \n\
+             Usage: app [OPTIONS]\n\
+             \n\
+             Options:\n  -o, --output <VALUE>\n\
+             \n\
+             Examples:\n  app -o out in.txt  basic usage\n  app in.txt         print to stdout\n\
+             \n\
+             Report bugs to the issue tracker.\n"
+        );
+        assert_eq!(
+            help_with(&ctx, "app", "{before-help}|{examples}"),
+            "app 1.0|Examples:\n  app -o out in.txt  basic usage\n  app in.txt         print to stdout"
+        );
+    }
+
+    #[test]
+    fn option_help_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            Token,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_arg("-o", "--output", TestState::Output)
+            .help(TestState::Output, "Output directory")
+            .env(TestState::Output, "MYAPP_OUT")
+            .default_value(TestState::Output, "./out")
+            .add_str("--token", TestState::Token)
+            .env(TestState::Token, "MYAPP_TOKEN")
+            .default_value(TestState::Token, "secret")
+            .hide_default(TestState::Token, true);
+
+        assert_eq!(
+            options(&ctx),
+            "Options:\n  -o, --output <VALUE>  Output directory [env: MYAPP_OUT] [default: ./out]\n  \
+             --token <VALUE>       [env: MYAPP_TOKEN]"
+        );
+    }
+}
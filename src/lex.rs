@@ -0,0 +1,276 @@
+//! Split command line arguments into [`Token`]s without binding them to any state.
+//!
+//! ```
+//! use cuteopt::lex::{lex, Token};
+//!
+//! let tokens: Vec<Token> = lex(vec!["--out=a.txt", "-v", "--", "-x"].into_iter().map(String::from))
+//!     .collect();
+//!
+//! assert_eq!(tokens[0], Token::Long(String::from("--out")));
+//! assert_eq!(tokens[1], Token::Value(String::from("a.txt")));
+//! assert_eq!(tokens[2], Token::Short(String::from("-v")));
+//! assert_eq!(tokens[3], Token::Terminator);
+//! assert_eq!(tokens[4], Token::Positional(String::from("-x")));
+//! ```
+
+pub const TERMINATOR: &str = "--";
+
+pub const DEFAULT_PREFIXES: &[&str] = &["-"];
+
+/// [`Token`] is one lexical item of the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// Long option with its prefix, such as `--output`
+    Long(String),
+    /// Short option with its prefix, such as `-o`
+    Short(String),
+    /// Value attached to the previous option with `=`
+    Value(String),
+    /// The `--` separator
+    Terminator,
+    /// Anything not looking like an option
+    Positional(String),
+}
+
+impl Token {
+    /// Return the token as it appeared on the command line, the `=` is not included
+    pub fn as_str(&self) -> &str {
+        match self {
+            Token::Long(s) | Token::Short(s) | Token::Value(s) | Token::Positional(s) => s,
+            Token::Terminator => TERMINATOR,
+        }
+    }
+
+    pub fn is_option(&self) -> bool {
+        matches!(self, Token::Long(_) | Token::Short(_))
+    }
+}
+
+/// Iterator adapter created by [`lex`]
+#[derive(Debug)]
+pub struct Lexer<I>
+where
+    I: Iterator<Item = String>,
+{
+    args: I,
+    pending: Option<Token>,
+    terminated: bool,
+}
+
+impl<I> Lexer<I>
+where
+    I: Iterator<Item = String>,
+{
+    pub fn new(args: I) -> Self {
+        Lexer {
+            args,
+            pending: None,
+            terminated: false,
+        }
+    }
+
+    /// Return true if the `--` separator has been seen
+    pub fn terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<I> Iterator for Lexer<I>
+where
+    I: Iterator<Item = String>,
+{
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if let Some(token) = self.pending.take() {
+            return Some(token);
+        }
+        let arg = self.args.next()?;
+
+        if self.terminated {
+            return Some(Token::Positional(arg));
+        }
+        if arg == TERMINATOR {
+            self.terminated = true;
+            return Some(Token::Terminator);
+        }
+        let (name, value) = split_inline(&arg);
+
+        if let Some(value) = value {
+            self.pending = Some(Token::Value(String::from(value)));
+        }
+        Some(classify(name))
+    }
+}
+
+/// Create a [`Lexer`] over the given arguments
+pub fn lex<I>(args: I) -> Lexer<I::IntoIter>
+where
+    I: IntoIterator<Item = String>,
+{
+    Lexer::new(args.into_iter())
+}
+
+/// Split `--name=value` into name and value, positionals are never split
+pub fn split_inline(arg: &str) -> (&str, Option<&str>) {
+    split_inline_with(arg, DEFAULT_PREFIXES)
+}
+
+/// Same as [`split_inline`], but using the given option prefixes
+pub fn split_inline_with<'a>(arg: &'a str, prefixes: &[&str]) -> (&'a str, Option<&'a str>) {
+    if looks_like_option_with(arg, prefixes) {
+        if let Some(index) = arg.find('=') {
+            return (&arg[..index], Some(&arg[index + 1..]));
+        }
+    }
+    (arg, None)
+}
+
+/// Return true if `arg` starts with `-` and is not the `-` or `--` itself
+pub fn looks_like_option(arg: &str) -> bool {
+    looks_like_option_with(arg, DEFAULT_PREFIXES)
+}
+
+/// Return true if `arg` starts with one of `prefixes` and is longer than the prefix,
+/// the `--` is never an option
+pub fn looks_like_option_with(arg: &str, prefixes: &[&str]) -> bool {
+    arg != TERMINATOR
+        && prefixes
+            .iter()
+            .any(|prefix| arg.len() > prefix.len() && arg.starts_with(prefix))
+}
+
+/// Return true if `arg` is a negative number such as `-5` or `-0.3`
+pub fn looks_like_number(arg: &str) -> bool {
+    match arg.strip_prefix('-') {
+        Some(number) => {
+            number.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                && number.parse::<f64>().is_ok()
+        }
+        None => false,
+    }
+}
+
+/// Split `arg` into grapheme clusters with their byte offset,
+/// fall back to `char`s without the `unicode` feature
+pub fn graphemes(arg: &str) -> Vec<(usize, &str)> {
+    #[cfg(feature = "unicode")]
+    {
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(arg, true).collect()
+    }
+    #[cfg(not(feature = "unicode"))]
+    {
+        arg.char_indices()
+            .map(|(offset, c)| (offset, &arg[offset..offset + c.len_utf8()]))
+            .collect()
+    }
+}
+
+fn classify(arg: &str) -> Token {
+    if arg.starts_with(TERMINATOR) {
+        Token::Long(String::from(arg))
+    } else if looks_like_option(arg) {
+        Token::Short(String::from(arg))
+    } else {
+        Token::Positional(String::from(arg))
+    }
+}
+
+/// Split the raw Windows command line into arguments with the rules of `CommandLineToArgvW`,
+/// the command line should not include the program name, such as the `lpCmdLine` of `WinMain`
+pub fn split_windows_cmdline(cmdline: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut chars = cmdline.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut arg = String::new();
+        let mut quoted = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let mut backslashes = 1;
+
+                    while chars.next_if_eq(&'\\').is_some() {
+                        backslashes += 1;
+                    }
+                    if chars.peek() == Some(&'"') {
+                        // 2n backslashes + quote is n backslashes and a delimiter,
+                        // 2n + 1 backslashes + quote is n backslashes and a literal quote
+                        arg.extend(std::iter::repeat('\\').take(backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            arg.push('"');
+                            chars.next();
+                        }
+                    } else {
+                        arg.extend(std::iter::repeat('\\').take(backslashes));
+                    }
+                }
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    arg.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                ' ' | '\t' if !quoted => break,
+                c => arg.push(c),
+            }
+        }
+        args.push(arg);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn lex_test() {
+        use super::*;
+
+        let args: Vec<String> = ["cmd", "-", "-o=", "--opt=a=b", "--", "--bool"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+        let tokens: Vec<Token> = lex(args).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Positional(String::from("cmd")),
+                Token::Positional(String::from("-")),
+                Token::Short(String::from("-o")),
+                Token::Value(String::new()),
+                Token::Long(String::from("--opt")),
+                Token::Value(String::from("a=b")),
+                Token::Terminator,
+                Token::Positional(String::from("--bool")),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_windows_cmdline_test() {
+        use super::*;
+
+        let split = |cmdline: &str| -> Vec<String> { split_windows_cmdline(cmdline) };
+        let args = |args: &[&str]| -> Vec<String> {
+            args.iter().map(|data| String::from(*data)).collect()
+        };
+
+        assert_eq!(split(r#"a b  c"#), args(&["a", "b", "c"]));
+        assert_eq!(split(r#""a b" c"#), args(&["a b", "c"]));
+        assert_eq!(split(r#"a\\\b d"e f"g h"#), args(&[r"a\\\b", "de fg", "h"]));
+        assert_eq!(split(r#"a\\\"b c d"#), args(&[r#"a\"b"#, "c", "d"]));
+        assert_eq!(split(r#"a\\\\"b c" d e"#), args(&[r"a\\b c", "d", "e"]));
+        assert_eq!(split(r#""" x"#), args(&["", "x"]));
+        assert_eq!(split(r#""a""b" c"#), args(&[r#"a"b"#, "c"]));
+        assert_eq!(
+            split(r#"C:\path\ "C:\Program Files\\""#),
+            args(&[r"C:\path\", r"C:\Program Files\"])
+        );
+        assert_eq!(split(" \t "), args(&[]));
+    }
+}
@@ -1,10 +1,118 @@
+pub mod app;
+pub mod complete;
+pub mod error;
+pub mod extension;
+pub mod help;
+pub mod lex;
+pub mod matcher;
+pub mod middleware;
+pub mod presets;
+pub mod registry;
+#[cfg(any(feature = "json", feature = "toml"))]
+pub mod spec;
+pub mod transform;
+pub mod val;
+pub mod verbosity;
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use complete::{Completer, ValueHint};
+pub use error::Error;
+use extension::Extensions;
+use matcher::{Glob, Matcher};
+use middleware::Middleware;
+use transform::Transform;
+use val::{ValueParser, ValueRefParser};
 
+#[allow(clippy::redundant_static_lifetimes)]
 const DEFAULT_STR: &'static str = "";
 
+const DEFAULT_TERMINATOR: &str = ";";
+
+/// Generate the [`Ctx`] from attributes of state enum.
+///
+/// ```
+/// use cuteopt::prelude::*;
+///
+/// #[derive(Debug, Clone, Eq, PartialEq, Default, StateOpt)]
+/// enum ParseState {
+///     #[cute(switch = "--verbose", alias = "-v", help = "Print more information")]
+///     Verbose,
+///     #[cute(option = "--output", help = "Set the output file")]
+///     Output,
+///     #[default]
+///     Default,
+/// }
+///
+/// let mut ctx = ParseState::build();
+/// let args = vec!["-v", "--output", "a.txt"];
+///
+/// ctx.parse(&mut args.into_iter().map(String::from)).unwrap();
+///
+/// assert!(ctx.get_value_as_bool(ParseState::Verbose));
+/// assert_eq!(ctx.get_value_as_str(ParseState::Output), "a.txt");
+/// ```
+#[cfg(feature = "derive")]
+pub use cuteopt_derive::StateOpt;
+
+/// Generate `TryFrom<&Ctx<S>>` for a struct, every field is parsed with [`ValueParser`].
+///
+/// ```
+/// use cuteopt::prelude::*;
+/// use std::convert::TryFrom;
+///
+/// #[derive(Debug, Clone, Eq, PartialEq, Default)]
+/// enum ParseState {
+///     Verbose,
+///     Jobs,
+///     #[default]
+///     Default,
+/// }
+///
+/// #[derive(Debug, FromCute)]
+/// #[cute(state = ParseState)]
+/// struct Config {
+///     #[cute(state = ParseState::Verbose)]
+///     verbose: bool,
+///     #[cute(state = ParseState::Jobs)]
+///     jobs: Option<usize>,
+///     untouched: String,
+/// }
+///
+/// let mut ctx = Ctx::new();
+///
+/// ctx.add_bool("-v", ParseState::Verbose)
+///     .add_str("-j", ParseState::Jobs);
+/// ctx.parse(&mut vec!["-j", "4"].into_iter().map(String::from)).unwrap();
+///
+/// let config = Config::try_from(&ctx).unwrap();
+///
+/// assert!(!config.verbose);
+/// assert_eq!(config.jobs, Some(4));
+/// ```
+#[cfg(feature = "derive")]
+pub use cuteopt_derive::FromCute;
+
 pub mod prelude {
+    pub use super::matcher::Matcher;
+    pub use super::val::{ValueParser, ValueRefParser};
     pub use super::Arg;
     pub use super::Ctx;
+    pub use super::EmptyValue;
+    pub use super::Error;
+    pub use super::Event;
+    pub use super::HyphenValue;
+    pub use super::Limits;
+    pub use super::Outcome;
+    pub use super::ParsePolicy;
+    #[cfg(feature = "derive")]
+    pub use super::{FromCute, StateOpt};
 }
 
 /// [`Arg`] hold option name and state
@@ -13,31 +121,56 @@ pub enum Arg<'a, S>
 where
     S: Debug + Clone + Eq + Default,
 {
-    Bool(&'a str, S),
-    Opt(&'a str, S),
+    Bool(Cow<'a, str>, S),
+    Opt(Cow<'a, str>, S),
+    Cmd(Cow<'a, str>, S),
+    Raw(Cow<'a, str>, S),
+    Pattern(Cow<'a, str>, S),
 }
 
 impl<'a, S> Arg<'a, S>
 where
     S: Debug + Clone + Eq + Default,
 {
-    pub fn name(&self) -> &'a str {
+    pub fn name(&self) -> &str {
         match self {
-            Arg::Bool(name, _) | Arg::Opt(name, _) => name,
+            Arg::Bool(name, _)
+            | Arg::Opt(name, _)
+            | Arg::Cmd(name, _)
+            | Arg::Raw(name, _)
+            | Arg::Pattern(name, _) => name,
         }
     }
 
     pub fn get_state(&self) -> &S {
         match self {
-            Arg::Bool(_, state) | Arg::Opt(_, state) => &state,
+            Arg::Bool(_, state)
+            | Arg::Opt(_, state)
+            | Arg::Cmd(_, state)
+            | Arg::Raw(_, state)
+            | Arg::Pattern(_, state) => state,
         }
     }
 
     pub fn is_bool(&self) -> bool {
-        match self {
-            Arg::Bool(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Arg::Bool(_, _))
+    }
+
+    /// Return true if the option take no argument, such as [`Arg::Bool`] and [`Arg::Cmd`]
+    pub fn is_switch(&self) -> bool {
+        matches!(self, Arg::Bool(_, _) | Arg::Cmd(_, _))
+    }
+
+    pub fn is_cmd(&self) -> bool {
+        matches!(self, Arg::Cmd(_, _))
+    }
+
+    pub fn is_raw(&self) -> bool {
+        matches!(self, Arg::Raw(_, _))
+    }
+
+    pub fn is_pattern(&self) -> bool {
+        matches!(self, Arg::Pattern(_, _))
     }
 }
 
@@ -46,6 +179,7 @@ where
 pub enum Value {
     Bool(bool),
     Str(String),
+    List(Vec<String>),
     None,
 }
 
@@ -55,16 +189,14 @@ impl Value {
         S: Debug + Clone + Eq + Default,
     {
         match arg {
-            Arg::Bool(_, _) => Value::Bool(true),
-            Arg::Opt(_, _) => Value::Str(value),
+            Arg::Bool(_, _) | Arg::Cmd(_, _) => Value::Bool(true),
+            Arg::Opt(_, _) | Arg::Pattern(_, _) => Value::Str(value),
+            Arg::Raw(_, _) => Value::List(vec![value]),
         }
     }
 
     pub fn is_bool(&self) -> bool {
-        match self {
-            Value::Bool(_) => true,
-            _ => false,
-        }
+        matches!(self, Value::Bool(_))
     }
 
     pub fn as_bool(&self) -> bool {
@@ -80,6 +212,61 @@ impl Value {
             _ => DEFAULT_STR,
         }
     }
+
+    pub fn as_list(&self) -> &[String] {
+        match self {
+            Value::List(list) => list,
+            _ => &[],
+        }
+    }
+}
+
+/// [`HyphenValue`] decide which `-` prefixed tokens can be consumed as option value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HyphenValue {
+    /// Consume any token, this is the default
+    #[default]
+    Any,
+    /// Consume negative numbers such as `-5` or `-0.3`, but not other option like tokens
+    Number,
+    /// Never consume option like tokens
+    Never,
+}
+
+/// [`EmptyValue`] decide what happen to the empty value such as `--name=`,
+/// a present option store [`Value::Str`] while the absent one is [`Value::None`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyValue {
+    /// Store the empty string, this is the default
+    #[default]
+    Allow,
+    /// Return an error
+    Error,
+    /// Treat the option as not present
+    Unset,
+}
+
+/// [`ParsePolicy`] decide what happen to the stored values when [`Ctx`] parse again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParsePolicy {
+    /// Keep the stored values, this is the default
+    #[default]
+    Accumulate,
+    /// Reset the stored values before every parse
+    Fresh,
+    /// Return an error if the [`Ctx`] has been parsed
+    ErrorIfParsed,
+}
+
+/// [`Limits`] protect the [`Ctx`] from untrusted arguments, `None` means no limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    /// The maximum number of arguments
+    pub max_tokens: Option<usize>,
+    /// The maximum length in bytes of a single value
+    pub max_value_len: Option<usize>,
+    /// The maximum number of values stored by an option
+    pub max_values: Option<usize>,
 }
 
 /// An simple option data struct
@@ -90,19 +277,120 @@ where
 {
     pub opt: Arg<'a, S>,
     pub value: Value,
+    pub aliases: Vec<Cow<'a, str>>,
+    pub require_equals: bool,
+    pub allow_hyphen_values: bool,
+    pub terminator: &'a str,
+    pub matcher: Option<Arc<dyn Matcher>>,
+    pub help: Cow<'a, str>,
+    pub hint: ValueHint,
+    pub completer: Option<Completer>,
+    pub count: usize,
+    pub transforms: Vec<Arc<dyn Transform>>,
+    pub delimiter: Option<char>,
+    pub multiple: bool,
+    pub meta: Vec<Arc<dyn Any + Send + Sync>>,
+    pub value_name: Cow<'a, str>,
+    pub empty_value: EmptyValue,
+    pub required_if: Vec<(S, String)>,
+    pub default_if: Vec<(S, Option<String>, String)>,
+    pub fallback: Option<S>,
+    pub exclusive: bool,
+    pub env: Option<Cow<'a, str>>,
+    pub default_value: Option<Cow<'a, str>>,
+    pub hide_default: bool,
+    pub profiles: Vec<Cow<'a, str>>,
 }
 
 impl<'a, S> OptKeeper<'a, S>
 where
     S: std::fmt::Debug + Clone + Default + Eq,
 {
-    pub fn name(&self) -> &'a str {
+    pub fn new(opt: Arg<'a, S>) -> Self {
+        let value = if opt.is_switch() {
+            Value::Bool(false)
+        } else {
+            Value::None
+        };
+        let matcher: Option<Arc<dyn Matcher>> = if opt.is_pattern() {
+            Some(Arc::new(Glob::new(opt.name())))
+        } else {
+            None
+        };
+        OptKeeper {
+            opt,
+            value,
+            aliases: vec![],
+            require_equals: false,
+            allow_hyphen_values: false,
+            terminator: DEFAULT_TERMINATOR,
+            matcher,
+            help: Cow::Borrowed(DEFAULT_STR),
+            hint: ValueHint::Unknown,
+            completer: None,
+            count: 0,
+            transforms: vec![],
+            delimiter: None,
+            multiple: false,
+            meta: vec![],
+            value_name: Cow::Borrowed(DEFAULT_STR),
+            empty_value: EmptyValue::Allow,
+            required_if: vec![],
+            default_if: vec![],
+            fallback: None,
+            exclusive: false,
+            env: None,
+            default_value: None,
+            hide_default: false,
+            profiles: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
         self.opt.name()
     }
 
     pub fn state(&self) -> &S {
         self.opt.get_state()
     }
+
+    /// Return true if the option has no value
+    pub fn is_unset(&self) -> bool {
+        matches!(self.value, Value::None | Value::Bool(false))
+    }
+
+    /// Return true if the option belong to `profile`, the option without profiles belong to all,
+    /// and all options belong to the `None` profile
+    pub fn in_profile(&self, profile: Option<&str>) -> bool {
+        match profile {
+            Some(profile) => {
+                self.profiles.is_empty() || self.profiles.iter().any(|exist| exist == profile)
+            }
+            None => true,
+        }
+    }
+
+    /// Return true if `name` is the option name or one of its aliases
+    pub fn is_named(&self, name: &str) -> bool {
+        self.matcher.is_none() && self.names().any(|n| n == name)
+    }
+
+    /// Return the option name and its aliases
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        Some(self.name())
+            .into_iter()
+            .chain(self.aliases.iter().map(|alias| alias.as_ref()))
+    }
+
+    /// Return the metadata of type `T` attached by [`Ctx::meta`]
+    pub fn get_meta<T: Any>(&self) -> Option<&T> {
+        self.meta.iter().find_map(|meta| meta.downcast_ref::<T>())
+    }
+
+    /// Match `arg` with the [`Matcher`] of option, return the captured value
+    pub fn match_with(&self, arg: &str) -> Option<Value> {
+        self.matcher.as_ref()?.r#match(arg)
+    }
 }
 
 /// [`Ctx`] hold all the [`OptKeeper`]s,
@@ -140,6 +428,65 @@ where
     S: std::fmt::Debug + Clone + Default + Eq,
 {
     opt_keeper_repo: Vec<OptKeeper<'a, S>>,
+    cmd_abbrev: bool,
+    hyphen_value: HyphenValue,
+    trailing: Option<(S, Value)>,
+    trailing_positional: bool,
+    cluster: bool,
+    posix: bool,
+    stop_at_unmatched: bool,
+    slash_alias: bool,
+    powershell: bool,
+    auto_short: bool,
+    profile: Option<Cow<'a, str>>,
+    parse_policy: ParsePolicy,
+    limits: Limits,
+    name_index: OnceCell<NameIndex>,
+    parsed: bool,
+    prefixes: Vec<&'a str>,
+    positionals: Option<(usize, Option<usize>)>,
+    positional_rest: Option<(S, Value, usize)>,
+    positional_names: Vec<(S, Cow<'a, str>)>,
+    before_help: Cow<'a, str>,
+    after_help: Cow<'a, str>,
+    examples: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    catch_all: Option<CatchAll<S>>,
+    dynamic: Vec<(S, Value)>,
+    expansions: Vec<(String, Vec<String>)>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    extensions: Extensions,
+    #[cfg(feature = "unicode")]
+    normalize: bool,
+}
+
+/// The index of option names and matchers, built at the first lookup
+#[derive(Debug, Default)]
+struct NameIndex {
+    names: HashMap<String, usize>,
+    matchers: Vec<usize>,
+    shorts: Vec<(usize, String)>,
+    dashed: HashMap<String, usize>,
+    slashed: HashMap<String, usize>,
+}
+
+/// The state of alias expansion during parse
+#[derive(Debug, Default)]
+struct Expansion {
+    pending: Vec<String>,
+    expanded: bool,
+    tokens: usize,
+}
+
+type CatchAllFn<S> = dyn Fn(&str) -> Option<(S, Value)> + Send + Sync;
+
+/// The handler set by [`Ctx::catch_all`]
+#[derive(Clone)]
+struct CatchAll<S>(Arc<CatchAllFn<S>>);
+
+impl<S> Debug for CatchAll<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CatchAll")
+    }
 }
 
 impl<'a, S> Ctx<'a, S>
@@ -149,185 +496,3887 @@ where
     pub fn new() -> Self {
         Ctx {
             opt_keeper_repo: vec![],
+            cmd_abbrev: false,
+            hyphen_value: HyphenValue::Any,
+            trailing: None,
+            trailing_positional: false,
+            cluster: false,
+            posix: false,
+            stop_at_unmatched: false,
+            slash_alias: false,
+            powershell: false,
+            auto_short: false,
+            profile: None,
+            parse_policy: ParsePolicy::Accumulate,
+            limits: Limits::default(),
+            name_index: OnceCell::new(),
+            parsed: false,
+            prefixes: vec![],
+            positionals: None,
+            positional_rest: None,
+            positional_names: vec![],
+            before_help: Cow::Borrowed(DEFAULT_STR),
+            after_help: Cow::Borrowed(DEFAULT_STR),
+            examples: vec![],
+            catch_all: None,
+            dynamic: vec![],
+            expansions: vec![],
+            middlewares: vec![],
+            extensions: Extensions::new(),
+            #[cfg(feature = "unicode")]
+            normalize: false,
         }
     }
 
     pub fn add(&mut self, arg: Arg<'a, S>) -> &mut Self {
-        let value = match arg {
-            Arg::Bool(_, _) => Value::Bool(false),
-            _ => Value::None,
-        };
-        self.opt_keeper_repo.push(OptKeeper { opt: arg, value });
+        self.name_index.take();
+        self.opt_keeper_repo.push(OptKeeper::new(arg));
         self
     }
 
-    pub fn add_bool(&mut self, name: &'a str, s: S) -> &mut Self {
-        self.opt_keeper_repo.push(OptKeeper {
-            opt: Arg::Bool(name, s),
-            value: Value::Bool(false),
-        });
+    pub fn add_bool(&mut self, name: impl Into<Cow<'a, str>>, s: S) -> &mut Self {
+        self.add(Arg::Bool(name.into(), s))
+    }
+
+    pub fn add_str(&mut self, name: impl Into<Cow<'a, str>>, s: S) -> &mut Self {
+        self.add(Arg::Opt(name.into(), s))
+    }
+
+    pub fn add_cmd(&mut self, name: impl Into<Cow<'a, str>>, s: S) -> &mut Self {
+        self.add(Arg::Cmd(name.into(), s))
+    }
+
+    /// Add a boolean option with both short and long name, such as `-v` and `--verbose`
+    pub fn add_flag(
+        &mut self,
+        short: impl Into<Cow<'a, str>>,
+        long: impl Into<Cow<'a, str>>,
+        s: S,
+    ) -> &mut Self {
+        let mut opt_keeper = OptKeeper::new(Arg::Bool(long.into(), s));
+
+        opt_keeper.aliases.push(short.into());
+        self.name_index.take();
+        self.opt_keeper_repo.push(opt_keeper);
         self
     }
 
-    pub fn add_str(&mut self, name: &'a str, s: S) -> &mut Self {
-        self.opt_keeper_repo.push(OptKeeper {
-            opt: Arg::Opt(name, s),
-            value: Value::None,
-        });
+    /// Add an option with argument with both short and long name, such as `-o` and `--output`
+    pub fn add_arg(
+        &mut self,
+        short: impl Into<Cow<'a, str>>,
+        long: impl Into<Cow<'a, str>>,
+        s: S,
+    ) -> &mut Self {
+        let mut opt_keeper = OptKeeper::new(Arg::Opt(long.into(), s));
+
+        opt_keeper.aliases.push(short.into());
+        self.name_index.take();
+        self.opt_keeper_repo.push(opt_keeper);
         self
     }
 
-    pub fn get(&self, s: S) -> Option<&Arg<'a, S>> {
-        for opt_keeper in self.opt_keeper_repo.iter() {
-            if opt_keeper.opt.get_state().clone() == s {
-                return Some(&opt_keeper.opt);
+    /// Add an option consuming all the following tokens until `terminator`,
+    /// like the `-exec ... ;` of `find`
+    pub fn add_raw(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        s: S,
+        terminator: &'a str,
+    ) -> &mut Self {
+        let mut opt_keeper = OptKeeper::new(Arg::Raw(name.into(), s));
+
+        opt_keeper.terminator = terminator;
+        self.name_index.take();
+        self.opt_keeper_repo.push(opt_keeper);
+        self
+    }
+
+    /// Add an option matching the glob `pattern` such as `-X*`,
+    /// the part matched by `*` is stored as value
+    pub fn add_pattern(&mut self, pattern: impl Into<Cow<'a, str>>, s: S) -> &mut Self {
+        self.add(Arg::Pattern(pattern.into(), s))
+    }
+
+    /// Add an option matching the regex `pattern`, the capture groups are stored as value
+    #[cfg(feature = "regex")]
+    pub fn add_regex(
+        &mut self,
+        pattern: impl Into<Cow<'a, str>>,
+        s: S,
+    ) -> Result<&mut Self, Error> {
+        let pattern = pattern.into();
+        let regex = regex::Regex::new(&format!("^(?:{})$", pattern))
+            .map_err(|e| Error::Config(format!("Invalid regex pattern `{}`: {}", pattern, e)))?;
+
+        let mut opt_keeper = OptKeeper::new(Arg::Pattern(pattern, s));
+
+        opt_keeper.matcher = Some(Arc::new(regex));
+        self.name_index.take();
+        self.opt_keeper_repo.push(opt_keeper);
+        Ok(self)
+    }
+
+    /// Move the options of `other` into `self` with their values,
+    /// fail without any change if an option name or state is already used
+    pub fn merge(&mut self, other: Ctx<'a, S>) -> Result<&mut Self, Error> {
+        for opt_keeper in other.opt_keeper_repo.iter() {
+            if let Some(exist) = self.opt_keeper_repo.iter().find(|ok| {
+                ok.state() == opt_keeper.state()
+                    || ok.names().any(|name| opt_keeper.names().any(|n| n == name))
+            }) {
+                return Err(Error::Config(format!(
+                    "Option conflict with the exist one {:?}: {:?}",
+                    exist.opt, opt_keeper.opt
+                )));
             }
         }
-        None
+        self.name_index.take();
+        self.opt_keeper_repo.extend(other.opt_keeper_repo);
+        if self.trailing.is_none() {
+            self.trailing = other.trailing;
+        }
+        if self.positional_rest.is_none() {
+            self.positional_rest = other.positional_rest;
+        }
+        for (state, name) in other.positional_names {
+            if self.positional_name(&state).is_none() {
+                self.positional_names.push((state, name));
+            }
+        }
+        Ok(self)
     }
 
-    pub fn has(&self, s: S) -> bool {
-        for opt_keeper in self.opt_keeper_repo.iter() {
-            if opt_keeper.opt.get_state().clone() == s {
-                return true;
-            }
+    /// Add a [`Middleware`] processing the arguments before matching,
+    /// all the arguments are collected from the iterator if there is any middleware
+    pub fn middleware(&mut self, middleware: impl Middleware + 'static) -> &mut Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Replace the first positional argument `name` with `tokens` before matching,
+    /// such as `b` to `build --release`
+    pub fn expand_alias<I>(&mut self, name: impl Into<String>, tokens: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let name = name.into();
+
+        self.expansions.retain(|(exist, _)| *exist != name);
+        self.expansions
+            .push((name, tokens.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    /// Set the handler called with the argument not matched by any option,
+    /// the returned state and value are stored like an option
+    pub fn catch_all(
+        &mut self,
+        handler: impl Fn(&str) -> Option<(S, Value)> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.catch_all = Some(CatchAll(Arc::new(handler)));
+        self
+    }
+
+    /// Match the option bound to state `s` with `matcher` instead of its name
+    pub fn matcher(&mut self, s: S, matcher: impl Matcher + 'static) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.matcher = Some(Arc::new(matcher));
         }
-        false
+        self
     }
 
-    pub fn get_value(&self, s: S) -> Option<&Value> {
-        for opt_keeper in self.opt_keeper_repo.iter() {
-            if opt_keeper.opt.get_state().clone() == s {
-                return Some(&opt_keeper.value);
-            }
+    /// Add a [`Transform`] applied to the value of the option bound to state `s` before it is stored
+    pub fn transform(&mut self, s: S, transform: impl Transform + 'static) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.transforms.push(Arc::new(transform));
         }
-        None
+        self
     }
 
-    pub fn get_value_as_bool(&self, s: S) -> bool {
-        if let Some(value) = self.get_value(s) {
-            value.as_bool()
-        } else {
-            false
+    /// Rewrite the value of the option bound to state `s` with `map` before it is stored
+    pub fn map_value(&mut self, s: S, map: fn(&str) -> String) -> &mut Self {
+        self.transform(s, transform::MapValue(map))
+    }
+
+    /// Trim the whitespace of the value of the option bound to state `s`
+    pub fn trim(&mut self, s: S) -> &mut Self {
+        self.transform(s, transform::Trim)
+    }
+
+    /// Convert the value of the option bound to state `s` into lowercase
+    pub fn lowercase(&mut self, s: S) -> &mut Self {
+        self.transform(s, transform::Lowercase)
+    }
+
+    /// Split the value of the option bound to state `s` with `delimiter`,
+    /// the values of every occurrence are collected into one [`Value::List`]
+    pub fn value_delimiter(&mut self, s: S, delimiter: char) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.delimiter = Some(delimiter);
+        }
+        self
+    }
+
+    /// Collect the values of every occurrence of the option bound to state `s` into one [`Value::List`]
+    pub fn multiple(&mut self, s: S, multiple: bool) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.multiple = multiple;
         }
+        self
     }
 
-    pub fn get_value_as_str(&self, s: S) -> &str {
-        if let Some(value) = self.get_value(s) {
-            value.as_str()
+    /// Attach metadata of type `T` to the option bound to state `s`,
+    /// replace the previous metadata of the same type
+    pub fn meta<T: Any + Send + Sync>(&mut self, s: S, meta: T) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.meta.retain(|meta| !meta.is::<T>());
+            opt_keeper.meta.push(Arc::new(meta));
+        }
+        self
+    }
+
+    /// Return the metadata of type `T` of the option bound to state `s`
+    pub fn get_meta<T: Any>(&self, s: S) -> Option<&T> {
+        self.opt_keeper_repo
+            .iter()
+            .find(|ok| *ok.state() == s)
+            .and_then(|ok| ok.get_meta::<T>())
+    }
+
+    /// Set the display name of the value of option bound to state `s`, such as `FILE`,
+    /// or the name of the positional arguments captured by [`Ctx::positional_rest`] and [`Ctx::trailing`]
+    pub fn value_name(&mut self, s: S, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        let name = name.into();
+
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.value_name = name;
         } else {
-            DEFAULT_STR
+            self.positional_names.retain(|(state, _)| *state != s);
+            self.positional_names.push((s, name));
         }
+        self
     }
 
-    pub fn len(&self) -> usize {
-        self.opt_keeper_repo.len()
+    /// Return the display name of the positional arguments captured under state `s`
+    pub fn positional_name(&self, s: &S) -> Option<&str> {
+        self.positional_names
+            .iter()
+            .find(|(state, _)| state == s)
+            .map(|(_, name)| name.as_ref())
     }
 
-    fn _get_opt_i32(&self, index: i32) -> &OptKeeper<'a, S> {
-        &self.opt_keeper_repo[index as usize]
+    /// Set the [`EmptyValue`] policy of the option bound to state `s`
+    pub fn empty_value(&mut self, s: S, empty_value: EmptyValue) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.empty_value = empty_value;
+        }
+        self
     }
 
-    fn _get_opt_mut_i32(&mut self, index: i32) -> &mut OptKeeper<'a, S> {
-        &mut self.opt_keeper_repo[index as usize]
+    /// Make the option bound to state `s` required when the option bound to `other` has `value`
+    pub fn required_if(&mut self, s: S, other: S, value: impl Into<String>) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.required_if.push((other, value.into()));
+        }
+        self
     }
 
-    pub fn parse(
-        &mut self,
-        args: &mut impl Iterator<Item = String>,
-    ) -> Result<Vec<String>, String> {
-        let mut while_flag = true;
-        let mut ret = vec![];
+    /// Read the value of the option bound to state `s` from the environment variable `name`
+    /// when it is not matched, the switch is false if the variable is empty, `0` or `false`
+    pub fn env(&mut self, s: S, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.env = Some(name.into());
+        }
+        self
+    }
 
-        while while_flag {
-            let mut current_index: i32 = -1;
+    /// Set the default value of the option bound to state `s` when it is not matched
+    /// and no environment variable or conditional default apply, the default is handled
+    /// like the value on command line, and the switch is false if it is empty, `0` or `false`
+    pub fn default_value(&mut self, s: S, default: impl Into<Cow<'a, str>>) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.default_value = Some(default.into());
+        }
+        self
+    }
 
-            match args.next() {
-                Some(arg) => {
-                    for index in 0..self.len() {
-                        if self._get_opt_i32(index as i32).name() == arg {
-                            current_index = index as i32;
-                            break;
-                        }
-                    }
+    /// Hide the default value of the option bound to state `s` in the help, such as a password
+    pub fn hide_default(&mut self, s: S, hide: bool) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.hide_default = hide;
+        }
+        self
+    }
 
-                    if current_index == -1 {
-                        ret.push(arg);
-                    }
-                }
-                None => {
-                    while_flag = false;
-                }
-            }
+    /// Tag the option bound to state `s` with `profiles`, it is only matched and
+    /// documented when one of them is activated by [`activate_profile`](Ctx::activate_profile)
+    pub fn profiles<I>(&mut self, s: S, profiles: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cow<'a, str>>,
+    {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.profiles = profiles.into_iter().map(Into::into).collect();
+        }
+        self
+    }
 
-            if current_index != -1 {
-                if self._get_opt_i32(current_index).opt.is_bool() {
-                    self._get_opt_mut_i32(current_index).value = Value::Bool(true);
-                } else {
-                    match args.next() {
-                        Some(value) => {
-                            self._get_opt_mut_i32(current_index).value = Value::Str(value);
-                        }
-                        None => {
-                            return Err(format!(
-                                "Option need argument: {:?}",
-                                self._get_opt_i32(current_index).opt
-                            ));
-                        }
-                    }
-                }
-            }
+    /// Set the default value of the option bound to state `s` when it is not matched,
+    /// the default only apply when the option bound to `other` matched,
+    /// or has the value `when` if it is not `None`
+    pub fn default_value_if(
+        &mut self,
+        s: S,
+        other: S,
+        when: Option<&str>,
+        default: impl Into<String>,
+    ) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper
+                .default_if
+                .push((other, when.map(String::from), default.into()));
         }
-        return Ok(ret);
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn opt_test() {
-        use super::*;
+    /// Let the value of state `s` fall back to the value of state `other` when it is unset
+    pub fn fallback(&mut self, s: S, other: S) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.fallback = Some(other);
+        }
+        self
+    }
 
-        #[derive(Debug, Clone, PartialEq, Eq)]
-        enum TestState {
-            CmdState,
-            BoolState,
-            OptionState,
-            HelpState,
-            UnknowState,
+    /// Set if the option bound to state `s` must appear alone, such as `--help`
+    pub fn exclusive(&mut self, s: S, exclusive: bool) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.exclusive = exclusive;
         }
+        self
+    }
 
-        impl Default for TestState {
-            fn default() -> TestState {
-                TestState::UnknowState
-            }
+    /// Register an alternate name for the option or command bound to state `s`
+    pub fn alias(&mut self, s: S, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.aliases.push(name.into());
         }
+        self
+    }
 
-        let mut ctx = Ctx::new();
+    /// Set the help message of the option bound to state `s`
+    pub fn help(&mut self, s: S, help: impl Into<Cow<'a, str>>) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.help = help.into();
+        }
+        self
+    }
 
-        ctx.add(Arg::Bool("-bool", TestState::BoolState));
-        ctx.add(Arg::Opt("-opt", TestState::OptionState));
-        ctx.add(Arg::Bool("/?", TestState::HelpState));
-        ctx.add(Arg::Bool("cmd", TestState::CmdState));
+    /// Set the text printed before the usage by [`help`](help::help)
+    pub fn before_help(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.before_help = text.into();
+        self
+    }
 
-        let args: Vec<String> = ["cmd", "-bool", "-opt", "value", "/?"]
-            .iter()
-            .map(|data| String::from(*data))
-            .collect();
+    /// Add an example command line with its description, printed by [`help`](help::help)
+    pub fn example(
+        &mut self,
+        command: impl Into<Cow<'a, str>>,
+        description: impl Into<Cow<'a, str>>,
+    ) -> &mut Self {
+        self.examples.push((command.into(), description.into()));
+        self
+    }
 
-        assert_eq!(ctx.parse(&mut args.into_iter()).is_ok(), true);
-        assert_eq!(
-            ctx.get_value(TestState::BoolState),
-            Some(&Value::Bool(true))
-        );
-        assert_eq!(
-            ctx.get_value(TestState::HelpState),
-            Some(&Value::Bool(true))
-        );
-        assert_eq!(ctx.get_value(TestState::CmdState), Some(&Value::Bool(true)));
-        assert_eq!(
-            ctx.get_value(TestState::OptionState),
-            Some(&Value::Str(String::from("value")))
-        );
+    /// Set the text printed after the options by [`help`](help::help)
+    pub fn after_help(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.after_help = text.into();
+        self
+    }
+
+    /// Set the [`ValueHint`] used by the shell completion of the option bound to state `s`
+    pub fn hint(&mut self, s: S, hint: ValueHint) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.hint = hint;
+        }
+        self
+    }
+
+    /// Set the [`Completer`] producing the candidate values of the option bound to state `s`
+    pub fn completer(&mut self, s: S, completer: Completer) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.completer = Some(completer);
+        }
+        self
+    }
+
+    /// Only accept the `name=value` form for the option bound to state `s`
+    pub fn require_equals(&mut self, s: S, require_equals: bool) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.require_equals = require_equals;
+        }
+        self
+    }
+
+    /// Accept unambiguous prefixes of command names and aliases, such as `rem` for `remove`
+    pub fn set_cmd_abbrev(&mut self, cmd_abbrev: bool) -> &mut Self {
+        self.cmd_abbrev = cmd_abbrev;
+        self
+    }
+
+    /// Let the option bound to state `s` consume any value regardless of [`Ctx::set_hyphen_value`]
+    pub fn allow_hyphen_values(&mut self, s: S, allow_hyphen_values: bool) -> &mut Self {
+        if let Some(opt_keeper) = self._find_state_mut(&s) {
+            opt_keeper.allow_hyphen_values = allow_hyphen_values;
+        }
+        self
+    }
+
+    pub fn set_hyphen_value(&mut self, hyphen_value: HyphenValue) -> &mut Self {
+        self.hyphen_value = hyphen_value;
+        self
+    }
+
+    /// Store everything after `--` as a [`Value::List`] under state `s`
+    pub fn trailing(&mut self, s: S) -> &mut Self {
+        self.trailing = Some((s, Value::List(vec![])));
+        self
+    }
+
+    /// Store every positional argument as a [`Value::List`] under state `s`,
+    /// the options can appear between them, the parse fail if less than `at_least` are found
+    pub fn positional_rest(&mut self, s: S, at_least: usize) -> &mut Self {
+        self.positional_rest = Some((s, Value::List(vec![]), at_least));
+        self
+    }
+
+    /// Start the [`Ctx::trailing`] capture at the first positional instead of at `--`
+    pub fn set_trailing_positional(&mut self, trailing_positional: bool) -> &mut Self {
+        self.trailing_positional = trailing_positional;
+        self
+    }
+
+    /// Compare option names and arguments in the Unicode NFC form
+    #[cfg(feature = "unicode")]
+    pub fn set_normalize(&mut self, normalize: bool) -> &mut Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Split unmatched tokens such as `-abc` into `-a -b -c`, the first option
+    /// need argument take the rest of the token as its value, every single character
+    /// prefix of [`Ctx::set_prefixes`] is splitted and the flags are grapheme clusters
+    /// with the `unicode` feature
+    pub fn set_cluster(&mut self, cluster: bool) -> &mut Self {
+        self.cluster = cluster;
+        self
+    }
+
+    /// Set the prefixes of option like tokens, default is `-`
+    pub fn set_prefixes<I>(&mut self, prefixes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.prefixes = prefixes.into_iter().collect();
+        self
+    }
+
+    /// Treat every argument after the first positional as positional,
+    /// like the `POSIXLY_CORRECT` of getopt
+    pub fn set_posix(&mut self, posix: bool) -> &mut Self {
+        self.posix = posix;
+        self
+    }
+
+    /// Stop the parse at the first positional argument, it is returned as the last unmatched
+    /// argument and the remaining arguments are left in the iterator
+    pub fn set_stop_at_unmatched(&mut self, stop_at_unmatched: bool) -> &mut Self {
+        self.stop_at_unmatched = stop_at_unmatched;
+        self
+    }
+
+    /// Accept `/name` for the option `-name` or `--name`, and `-name` or `--name` for the option `/name`
+    pub fn set_slash_alias(&mut self, slash_alias: bool) -> &mut Self {
+        self.name_index.take();
+        self.slash_alias = slash_alias;
+        self
+    }
+
+    /// Match the option like PowerShell, the name is case insensitive and can be abbreviated
+    /// to an unambiguous prefix, the value can be attached with `:` such as `-Path:a.txt`,
+    /// and the switch accept `:$true` or `:$false`
+    pub fn set_powershell(&mut self, powershell: bool) -> &mut Self {
+        self.powershell = powershell;
+        self
+    }
+
+    /// Assign an unused short name such as `-o` to each `--long` option without a short name,
+    /// the letters of the long name are tried in order
+    pub fn set_auto_short(&mut self, auto_short: bool) -> &mut Self {
+        self.name_index.take();
+        self.auto_short = auto_short;
+        self
+    }
+
+    /// Return the short name assigned by [`set_auto_short`](Ctx::set_auto_short) to the option bound to state `s`
+    pub fn auto_short_of(&self, s: &S) -> Option<&str> {
+        self._name_index()
+            .shorts
+            .iter()
+            .find(|(index, _)| self.opt_keeper_repo[*index].state() == s)
+            .map(|(_, short)| short.as_str())
+    }
+
+    /// Activate the profile `name`, the options tagged with other [`profiles`](Ctx::profiles)
+    /// are ignored by parse and help, all options are active before any profile is activated
+    pub fn activate_profile(&mut self, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.name_index.take();
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Return the name of the active profile
+    pub fn active_profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Iterate the options belong to the active profile
+    pub fn active(&self) -> impl Iterator<Item = &OptKeeper<'a, S>> + '_ {
+        self.opt_keeper_repo
+            .iter()
+            .filter(move |ok| self._is_active(ok))
+    }
+
+    /// Set the [`ParsePolicy`] used when parse more than once
+    pub fn set_parse_policy(&mut self, parse_policy: ParsePolicy) -> &mut Self {
+        self.parse_policy = parse_policy;
+        self
+    }
+
+    /// Set the [`Limits`] checked during parse
+    pub fn set_limits(&mut self, limits: Limits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Return true if the [`Ctx`] has been parsed
+    pub fn parsed(&self) -> bool {
+        self.parsed
+    }
+
+    /// Return a new [`Ctx`] with the same options and settings but no value,
+    /// the [`Extensions`] are not copied
+    pub fn snapshot(&self) -> Self {
+        let mut ctx = Ctx {
+            opt_keeper_repo: self.opt_keeper_repo.clone(),
+            cmd_abbrev: self.cmd_abbrev,
+            hyphen_value: self.hyphen_value,
+            trailing: self.trailing.clone(),
+            trailing_positional: self.trailing_positional,
+            cluster: self.cluster,
+            posix: self.posix,
+            stop_at_unmatched: self.stop_at_unmatched,
+            slash_alias: self.slash_alias,
+            powershell: self.powershell,
+            auto_short: self.auto_short,
+            profile: self.profile.clone(),
+            parse_policy: self.parse_policy,
+            limits: self.limits,
+            name_index: OnceCell::new(),
+            parsed: self.parsed,
+            prefixes: self.prefixes.clone(),
+            positionals: self.positionals,
+            positional_rest: self.positional_rest.clone(),
+            positional_names: self.positional_names.clone(),
+            before_help: self.before_help.clone(),
+            after_help: self.after_help.clone(),
+            examples: self.examples.clone(),
+            catch_all: self.catch_all.clone(),
+            dynamic: vec![],
+            expansions: self.expansions.clone(),
+            middlewares: self.middlewares.clone(),
+            extensions: Extensions::new(),
+            #[cfg(feature = "unicode")]
+            normalize: self.normalize,
+        };
+
+        ctx.reset();
+        ctx
+    }
+
+    /// Clear the stored values and counts, the option definitions are kept
+    pub fn reset(&mut self) -> &mut Self {
+        for opt_keeper in self.opt_keeper_repo.iter_mut() {
+            opt_keeper.value = OptKeeper::new(opt_keeper.opt.clone()).value;
+            opt_keeper.count = 0;
+        }
+        if let Some((_, value)) = self.trailing.as_mut() {
+            *value = Value::List(vec![]);
+        }
+        if let Some((_, value, _)) = self.positional_rest.as_mut() {
+            *value = Value::List(vec![]);
+        }
+        self.dynamic.clear();
+        self.parsed = false;
+        self
+    }
+
+    /// Make the parse fail if the number of positional arguments left is not in `range`
+    pub fn expect_positionals(&mut self, range: impl RangeBounds<usize>) -> &mut Self {
+        let min = match range.start_bound() {
+            Bound::Included(min) => *min,
+            Bound::Excluded(min) => min + 1,
+            Bound::Unbounded => 0,
+        };
+        let max = match range.end_bound() {
+            Bound::Included(max) => Some(*max),
+            Bound::Excluded(max) => Some(max.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+
+        self.positionals = Some((min, max));
+        self
+    }
+
+    /// Return true if `arg` starts with one of the [`Ctx::set_prefixes`]
+    pub fn looks_like_option(&self, arg: &str) -> bool {
+        lex::looks_like_option_with(arg, self._prefixes())
+    }
+
+    /// Return the [`Extensions`] storing data of any type
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    pub fn get(&self, s: S) -> Option<&Arg<'a, S>> {
+        for opt_keeper in self.opt_keeper_repo.iter() {
+            if *opt_keeper.state() == s {
+                return Some(&opt_keeper.opt);
+            }
+        }
+        None
+    }
+
+    pub fn has(&self, s: S) -> bool {
+        for opt_keeper in self.opt_keeper_repo.iter() {
+            if *opt_keeper.state() == s {
+                return true;
+            }
+        }
+        matches!(&self.trailing, Some((state, _)) if *state == s)
+            || matches!(&self.positional_rest, Some((state, _, _)) if *state == s)
+            || self.dynamic.iter().any(|(state, _)| *state == s)
+    }
+
+    pub fn get_value(&self, s: S) -> Option<&Value> {
+        let s = self._resolve_fallback(s);
+
+        for opt_keeper in self.opt_keeper_repo.iter() {
+            if *opt_keeper.state() == s {
+                return Some(&opt_keeper.value);
+            }
+        }
+        match (&self.trailing, &self.positional_rest) {
+            (Some((state, value)), _) if *state == s => Some(value),
+            (_, Some((state, value, _))) if *state == s => Some(value),
+            _ => self
+                .dynamic
+                .iter()
+                .find(|(state, _)| *state == s)
+                .map(|(_, value)| value),
+        }
+    }
+
+    pub fn get_value_as_bool(&self, s: S) -> bool {
+        if let Some(value) = self.get_value(s) {
+            value.as_bool()
+        } else {
+            false
+        }
+    }
+
+    pub fn get_value_as_str(&self, s: S) -> &str {
+        if let Some(value) = self.get_value(s) {
+            value.as_str()
+        } else {
+            DEFAULT_STR
+        }
+    }
+
+    /// Parse the value of state `s` with [`ValueParser`], the error is [`Error::Missing`]
+    /// if the option has no value, otherwise [`Error::Value`]
+    pub fn get_value_as<T: ValueParser>(&self, s: S) -> Result<T, Error> {
+        let value = self.get_value(s.clone()).unwrap_or(&Value::None);
+
+        // the count of the state providing the value
+        let count = self.get_count(self._resolve_fallback(s.clone()));
+
+        T::parse_with_count(value, count)
+            .map_err(|e| Self::_value_error(value, format!("{}: {:?}", e, s)))
+    }
+
+    /// Parse the single value of state `s` with the closure `parse`
+    pub fn get_value_with<T, E, F>(&self, s: S, parse: F) -> Result<T, Error>
+    where
+        E: std::fmt::Display,
+        F: FnOnce(&str) -> Result<T, E>,
+    {
+        let value = self.get_value(s.clone()).unwrap_or(&Value::None);
+        let string = val::as_single(value)
+            .map_err(|e| Self::_value_error(value, format!("{}: {:?}", e, s)))?;
+
+        parse(string)
+            .map_err(|e| Error::Value(format!("Invalid value `{}`: {}: {:?}", string, e, s)))
+    }
+
+    /// Parse the value of state `s` with [`ValueRefParser`], the result borrow from the [`Ctx`]
+    pub fn get_value_ref<'v, T: ValueRefParser<'v>>(&'v self, s: S) -> Result<T, Error> {
+        let value = self.get_value(s.clone()).unwrap_or(&Value::None);
+
+        T::parse_ref(value).map_err(|e| Self::_value_error(value, format!("{}: {:?}", e, s)))
+    }
+
+    /// Return how many times the option of state `s` appeared on the command line
+    pub fn get_count(&self, s: S) -> usize {
+        self.opt_keeper_repo
+            .iter()
+            .find(|ok| *ok.state() == s)
+            .map_or(0, |ok| ok.count)
+    }
+
+    pub fn get_value_as_list(&self, s: S) -> &[String] {
+        if let Some(value) = self.get_value(s) {
+            value.as_list()
+        } else {
+            &[]
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.opt_keeper_repo.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.opt_keeper_repo.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, OptKeeper<'a, S>> {
+        self.opt_keeper_repo.iter()
+    }
+
+    fn _get_opt_i32(&self, index: i32) -> &OptKeeper<'a, S> {
+        &self.opt_keeper_repo[index as usize]
+    }
+
+    fn _get_opt_mut_i32(&mut self, index: i32) -> &mut OptKeeper<'a, S> {
+        &mut self.opt_keeper_repo[index as usize]
+    }
+
+    fn _prefixes(&self) -> &[&'a str] {
+        if self.prefixes.is_empty() {
+            lex::DEFAULT_PREFIXES
+        } else {
+            &self.prefixes
+        }
+    }
+
+    fn _is_active(&self, opt_keeper: &OptKeeper<'a, S>) -> bool {
+        opt_keeper.in_profile(self.profile.as_deref())
+    }
+
+    /// Return [`Error::Missing`] if the parser failed because the option has no value
+    fn _value_error(value: &Value, message: String) -> Error {
+        match value {
+            Value::None => Error::Missing(message),
+            _ => Error::Value(message),
+        }
+    }
+
+    fn _find_state(&self, s: &S) -> Option<&OptKeeper<'a, S>> {
+        self.opt_keeper_repo.iter().find(|ok| ok.state() == s)
+    }
+
+    fn _find_state_mut(&mut self, s: &S) -> Option<&mut OptKeeper<'a, S>> {
+        // the names of option may be changed
+        self.name_index.take();
+        self.opt_keeper_repo.iter_mut().find(|ok| ok.state() == s)
+    }
+
+    fn _accept_value(&self, opt_keeper: &OptKeeper<'a, S>, value: &str) -> bool {
+        if opt_keeper.allow_hyphen_values || !self.looks_like_option(value) {
+            return true;
+        }
+        match self.hyphen_value {
+            HyphenValue::Any => true,
+            HyphenValue::Number => lex::looks_like_number(value),
+            HyphenValue::Never => false,
+        }
+    }
+
+    fn _name_index(&self) -> &NameIndex {
+        self.name_index.get_or_init(|| {
+            let mut name_index = NameIndex::default();
+
+            for (index, opt_keeper) in self.opt_keeper_repo.iter().enumerate() {
+                if !self._is_active(opt_keeper) {
+                    continue;
+                }
+                if opt_keeper.matcher.is_some() {
+                    name_index.matchers.push(index);
+                } else {
+                    for name in opt_keeper.names() {
+                        name_index.names.entry(String::from(name)).or_insert(index);
+                    }
+                }
+            }
+            if self.auto_short {
+                name_index.shorts = self._assign_shorts(&name_index.names);
+                for (index, short) in name_index.shorts.iter() {
+                    name_index.names.insert(short.clone(), *index);
+                }
+            }
+            if self.slash_alias {
+                // the `-name` is preferred to `--name` for `/name`
+                for prefix in ["-", "--", "/"] {
+                    for (name, index) in name_index.names.iter() {
+                        if let Some(bare) = name.strip_prefix(prefix) {
+                            let names = if prefix == "/" {
+                                &mut name_index.slashed
+                            } else {
+                                &mut name_index.dashed
+                            };
+
+                            names.entry(String::from(bare)).or_insert(*index);
+                        }
+                    }
+                }
+            }
+            name_index
+        })
+    }
+
+    fn _assign_shorts(&self, names: &HashMap<String, usize>) -> Vec<(usize, String)> {
+        let mut shorts: Vec<(usize, String)> = vec![];
+        let is_short = |name: &str| {
+            name.strip_prefix('-')
+                .is_some_and(|c| c.chars().count() == 1)
+        };
+
+        for (index, opt_keeper) in self.opt_keeper_repo.iter().enumerate() {
+            if opt_keeper.matcher.is_some()
+                || opt_keeper.opt.is_cmd()
+                || opt_keeper.names().any(is_short)
+                || !self._is_active(opt_keeper)
+            {
+                continue;
+            }
+            let long = match opt_keeper.names().find_map(|name| name.strip_prefix("--")) {
+                Some(long) => long,
+                None => continue,
+            };
+            let short = long
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .flat_map(|c| [c.to_ascii_lowercase(), c.to_ascii_uppercase()])
+                .map(|c| format!("-{}", c))
+                .find(|short| {
+                    !names.contains_key(short) && shorts.iter().all(|(_, exist)| exist != short)
+                });
+
+            if let Some(short) = short {
+                shorts.push((index, short));
+            }
+        }
+        shorts
+    }
+
+    fn _find_powershell(&self, arg: &str) -> Option<usize> {
+        let mut candidates = vec![];
+
+        for (index, opt_keeper) in self.opt_keeper_repo.iter().enumerate() {
+            if opt_keeper.matcher.is_some()
+                || opt_keeper.opt.is_cmd()
+                || !self._is_active(opt_keeper)
+            {
+                continue;
+            }
+            for name in opt_keeper.names() {
+                if name.eq_ignore_ascii_case(arg) {
+                    return Some(index);
+                }
+                let is_prefix = name
+                    .get(..arg.len())
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(arg));
+
+                if is_prefix && !candidates.contains(&index) {
+                    candidates.push(index);
+                }
+            }
+        }
+        match candidates.as_slice() {
+            [index] => Some(*index),
+            _ => None,
+        }
+    }
+
+    fn _find_slash_alias(&self, arg: &str) -> Option<usize> {
+        let name_index = self._name_index();
+
+        match arg.strip_prefix('/') {
+            Some("") => None,
+            Some(name) => name_index.dashed.get(name).copied(),
+            None => {
+                let name = arg.trim_start_matches('-');
+
+                if name.is_empty() || name.len() == arg.len() || arg.len() - name.len() > 2 {
+                    None
+                } else {
+                    name_index.slashed.get(name).copied()
+                }
+            }
+        }
+    }
+
+    fn _find_index(&self, arg: &str) -> i32 {
+        if let Some(index) = self._name_index().names.get(arg) {
+            return *index as i32;
+        }
+        if self.slash_alias {
+            if let Some(index) = self._find_slash_alias(arg) {
+                return index as i32;
+            }
+        }
+        if self.powershell && self.looks_like_option(arg) {
+            if let Some(index) = self._find_powershell(arg) {
+                return index as i32;
+            }
+        }
+        #[cfg(feature = "unicode")]
+        if self.normalize {
+            use unicode_normalization::UnicodeNormalization;
+
+            let arg: String = arg.nfc().collect();
+
+            if let Some(index) = self.opt_keeper_repo.iter().position(|ok| {
+                ok.matcher.is_none()
+                    && self._is_active(ok)
+                    && ok.names().any(|name| name.nfc().eq(arg.chars()))
+            }) {
+                return index as i32;
+            }
+        }
+        if self.cmd_abbrev && !arg.is_empty() {
+            let mut candidates = self.opt_keeper_repo.iter().enumerate().filter(|(_, ok)| {
+                ok.opt.is_cmd()
+                    && self._is_active(ok)
+                    && (ok.name().starts_with(arg) || ok.aliases.iter().any(|a| a.starts_with(arg)))
+            });
+
+            if let (Some((index, _)), None) = (candidates.next(), candidates.next()) {
+                return index as i32;
+            }
+        }
+        -1
+    }
+
+    fn _next_match(
+        &self,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Option<Result<Matched<S>, Error>> {
+        let arg = args.next()?;
+        let mut index = self._find_index(&arg);
+        let mut inline = None;
+        // the offset of inline value, the value reuse the buffer of `arg`
+        let mut inline_at = None;
+
+        if index == -1 {
+            if let (name, Some(_)) = lex::split_inline_with(&arg, self._prefixes()) {
+                index = self._find_index(name);
+                inline_at = Some(name.len() + 1);
+            }
+        }
+        if index == -1 && self.powershell && self.looks_like_option(&arg) {
+            if let Some((name, _)) = arg.split_once(':') {
+                index = self._find_index(name);
+                inline_at = Some(name.len() + 1);
+            }
+        }
+        if index == -1 {
+            for &matcher_index in self._name_index().matchers.iter() {
+                let opt_keeper = &self.opt_keeper_repo[matcher_index];
+
+                match opt_keeper.match_with(&arg) {
+                    Some(value) if opt_keeper.opt.is_pattern() => {
+                        return Some(
+                            self._transform(opt_keeper, value)
+                                .map(|value| Matched::Opt(matcher_index as i32, value)),
+                        );
+                    }
+                    Some(value) => {
+                        index = matcher_index as i32;
+                        inline_at = None;
+                        inline = match value {
+                            Value::Str(value) => Some(value),
+                            _ => None,
+                        };
+                        break;
+                    }
+                    None => {}
+                }
+            }
+        }
+        if index == -1 {
+            if self.trailing.is_some()
+                && (arg == lex::TERMINATOR
+                    || (self.trailing_positional && !self.looks_like_option(&arg)))
+            {
+                let mut list: Vec<String> = vec![];
+
+                if arg != lex::TERMINATOR {
+                    list.push(arg);
+                }
+                list.extend(args);
+                return Some(Ok(Matched::Trailing(Value::List(list))));
+            }
+            if self.cluster {
+                if let Some(matched) = self._match_cluster(&arg, args) {
+                    return Some(matched);
+                }
+            }
+            if let Some(catch_all) = &self.catch_all {
+                if let Some((state, value)) = (catch_all.0)(&arg) {
+                    return Some(Ok(Matched::Dynamic(state, value)));
+                }
+            }
+            if self.posix && !self.looks_like_option(&arg) {
+                return Some(Ok(Matched::Operands(
+                    Some(arg).into_iter().chain(args).collect(),
+                )));
+            }
+            return Some(Ok(Matched::Unmatched(arg)));
+        }
+        if let Some(offset) = inline_at {
+            let mut value = arg;
+
+            value.drain(..offset);
+            inline = Some(value);
+        }
+        Some(
+            self._match_opt(index, inline, args)
+                .map(|(index, value)| Matched::Opt(index, value)),
+        )
+    }
+
+    fn _match_cluster(
+        &self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Option<Result<Matched<S>, Error>> {
+        if !self.looks_like_option(arg) {
+            return None;
+        }
+        let (prefix, flags) = self
+            ._prefixes()
+            .iter()
+            .filter(|prefix| prefix.chars().count() == 1)
+            .find_map(|prefix| {
+                arg.strip_prefix(prefix)
+                    .filter(|flags| !flags.is_empty() && !flags.starts_with(prefix))
+                    .map(|flags| (*prefix, flags))
+            })?;
+        let mut matched = vec![];
+        let mut name = String::from(prefix);
+
+        for (offset, flag) in lex::graphemes(flags) {
+            name.truncate(prefix.len());
+            name.push_str(flag);
+
+            let index = self._find_index(&name);
+
+            if index == -1 {
+                return None;
+            }
+            if !self._get_opt_i32(index).opt.is_switch() {
+                let rest = &flags[offset + flag.len()..];
+                let rest = rest.strip_prefix('=').unwrap_or(rest);
+                let inline = if rest.is_empty() {
+                    None
+                } else {
+                    Some(String::from(rest))
+                };
+
+                matched.push(self._match_opt(index, inline, args));
+                break;
+            }
+            matched.push(Ok((index, Value::Bool(true))));
+        }
+        Some(
+            matched
+                .into_iter()
+                .collect::<Result<_, _>>()
+                .map(Matched::Cluster),
+        )
+    }
+
+    fn _match_opt(
+        &self,
+        index: i32,
+        inline: Option<String>,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<(i32, Value), Error> {
+        let (index, value) = self._match_value(index, inline, args)?;
+        let opt_keeper = self._get_opt_i32(index);
+        let value = match (value, opt_keeper.delimiter) {
+            (Value::Str(value), Some(delimiter)) => {
+                Value::List(value.split(delimiter).map(String::from).collect())
+            }
+            (value, _) => value,
+        };
+
+        match self._transform(opt_keeper, value)? {
+            Value::Str(value) if value.is_empty() => match opt_keeper.empty_value {
+                EmptyValue::Allow => Ok((index, Value::Str(value))),
+                EmptyValue::Error => Err(Error::Value(format!(
+                    "Option value can not be empty: {:?}",
+                    opt_keeper.opt
+                ))),
+                EmptyValue::Unset => Ok((index, Value::None)),
+            },
+            value => Ok((index, value)),
+        }
+    }
+
+    fn _transform(&self, opt_keeper: &OptKeeper<'a, S>, value: Value) -> Result<Value, Error> {
+        if opt_keeper.transforms.is_empty() {
+            return Ok(value);
+        }
+        let transform = |mut value: String| {
+            for transform in opt_keeper.transforms.iter() {
+                value = transform
+                    .transform(value)
+                    .map_err(|e| Error::Value(format!("{}: {:?}", e, opt_keeper.opt)))?;
+            }
+            Ok(value)
+        };
+
+        match value {
+            Value::Str(value) => transform(value).map(Value::Str),
+            Value::List(list) => list
+                .into_iter()
+                .map(transform)
+                .collect::<Result<_, _>>()
+                .map(Value::List),
+            value => Ok(value),
+        }
+    }
+
+    fn _match_value(
+        &self,
+        index: i32,
+        inline: Option<String>,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<(i32, Value), Error> {
+        let opt_keeper = self._get_opt_i32(index);
+        let opt = &opt_keeper.opt;
+
+        if opt.is_raw() {
+            let mut list: Vec<String> = inline.into_iter().collect();
+
+            loop {
+                match args.next() {
+                    Some(value) if value == opt_keeper.terminator => {
+                        break Ok((index, Value::List(list)));
+                    }
+                    Some(value) => list.push(value),
+                    None => {
+                        break Err(Error::Missing(format!(
+                            "Option need a terminator `{}`: {:?}",
+                            opt_keeper.terminator, opt
+                        )));
+                    }
+                }
+            }
+        } else if opt.is_switch() {
+            match inline {
+                Some(value) if self.powershell => match value.to_ascii_lowercase().as_str() {
+                    "$true" | "true" => Ok((index, Value::Bool(true))),
+                    "$false" | "false" => Ok((index, Value::Bool(false))),
+                    _ => Err(Error::Value(format!(
+                        "Option expect `$true` or `$false`: {:?}",
+                        opt
+                    ))),
+                },
+                Some(_) => Err(Error::Argument(format!(
+                    "Option not accept argument: {:?}",
+                    opt
+                ))),
+                None => Ok((index, Value::Bool(true))),
+            }
+        } else if let Some(value) = inline {
+            Ok((index, Value::Str(value)))
+        } else if opt_keeper.require_equals {
+            Err(Error::Argument(format!(
+                "Option need argument in the form `{}=VALUE`: {:?}",
+                opt.name(),
+                opt
+            )))
+        } else {
+            match args.next() {
+                Some(value) if self._accept_value(opt_keeper, &value) => {
+                    Ok((index, Value::Str(value)))
+                }
+                Some(value) => Err(Error::Missing(format!(
+                    "Option need argument, `{}` looks like an option: {:?}",
+                    value, opt
+                ))),
+                None => Err(Error::Missing(format!("Option need argument: {:?}", opt))),
+            }
+        }
+    }
+
+    pub fn parse(&mut self, args: &mut impl Iterator<Item = String>) -> Result<Vec<String>, Error> {
+        if !self.middlewares.is_empty() || self.limits.max_tokens.is_some() {
+            return self._parse(&mut self._preprocess(args)?.into_iter());
+        }
+        self._parse(args)
+    }
+
+    fn _parse(&mut self, args: &mut impl Iterator<Item = String>) -> Result<Vec<String>, Error> {
+        match self._parse_outcome(args, false)? {
+            Outcome::Continue(ret) => Ok(ret),
+            Outcome::Exit(_) => Ok(vec![]),
+        }
+    }
+
+    /// Same as [`parse`](Ctx::parse), but return [`Outcome::Exit`] as soon as
+    /// an [`exclusive`](Ctx::exclusive) option matched, the remaining arguments are not parsed
+    pub fn parse_outcome(
+        &mut self,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<Outcome<S>, Error> {
+        if !self.middlewares.is_empty() || self.limits.max_tokens.is_some() {
+            return self._parse_outcome(&mut self._preprocess(args)?.into_iter(), true);
+        }
+        self._parse_outcome(args, true)
+    }
+
+    fn _parse_outcome(
+        &mut self,
+        args: &mut impl Iterator<Item = String>,
+        early_exit: bool,
+    ) -> Result<Outcome<S>, Error> {
+        let mut ret = vec![];
+        let mut expansion = Expansion::default();
+        let mut total = 0;
+        let mut exclusive = None;
+
+        self._begin_parse()?;
+        while let Some(matched) = self._next_expanded(args, &mut expansion) {
+            let matched = matched?;
+            let stop = self._stop_at(&matched);
+
+            self._check_limits(&matched)?;
+            total += matched.len();
+            exclusive = exclusive.or_else(|| self._exclusive(&matched));
+            self._apply(matched, &mut ret);
+            if let Some(s) = exclusive.as_ref().filter(|_| early_exit) {
+                return Ok(Outcome::Exit(s.clone()));
+            }
+            if stop {
+                break;
+            }
+        }
+        if let Some(s) = exclusive {
+            return self._check_exclusive(s, total).map(Outcome::Exit);
+        }
+        self._check_rest()?;
+        self._apply_defaults()?;
+        self._check_required()?;
+        self._check_positionals(
+            ret.iter()
+                .filter(|arg| *arg != lex::TERMINATOR && !self.looks_like_option(arg))
+                .count(),
+        )?;
+        Ok(Outcome::Continue(ret))
+    }
+
+    /// Split the raw Windows command line with [`lex::split_windows_cmdline`] and parse it,
+    /// the command line should not include the program name
+    pub fn parse_windows_cmdline(&mut self, cmdline: &str) -> Result<Vec<String>, Error> {
+        self.parse(&mut lex::split_windows_cmdline(cmdline).into_iter())
+    }
+
+    /// Extract the values of `states` from `args` into a [`snapshot`](Ctx::snapshot),
+    /// other arguments are skipped and only the missing value of option is reported, so the
+    /// configuration file or log level can be loaded before the full parse, the [`Ctx`]
+    /// itself is not changed.
+    ///
+    /// The arguments are preprocessed and matched like parse, include the
+    /// [`middleware`](Ctx::middleware)s, [`expand_alias`](Ctx::expand_alias),
+    /// [`set_posix`](Ctx::set_posix) and [`set_stop_at_unmatched`](Ctx::set_stop_at_unmatched),
+    /// the arguments are scanned as is if one of the middlewares failed
+    pub fn prescan<I>(&self, args: I, states: impl IntoIterator<Item = S>) -> Result<Self, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let states: Vec<S> = states.into_iter().collect();
+        let mut ctx = self.snapshot();
+        let tokens: Vec<String> = args
+            .into_iter()
+            .map(|arg| String::from(arg.as_ref()))
+            .collect();
+        let mut args = self
+            ._preprocess(&mut tokens.iter().cloned())
+            .unwrap_or(tokens)
+            .into_iter();
+
+        let mut expansion = Expansion::default();
+
+        while let Some(matched) = ctx._next_expanded(&mut args, &mut expansion) {
+            let matched = match matched {
+                Ok(matched) => matched,
+                Err(e @ Error::Missing(_)) => return Err(e),
+                Err(_) => continue,
+            };
+            let stop = ctx._stop_at(&matched);
+            let matched = match matched {
+                Matched::Opt(index, value) => vec![(index, value)],
+                Matched::Cluster(matched) => matched,
+                Matched::Dynamic(state, value) => {
+                    if states.contains(&state) {
+                        ctx._apply(Matched::Dynamic(state, value), &mut vec![]);
+                    }
+                    vec![]
+                }
+                Matched::Unmatched(arg) if arg != lex::TERMINATOR => vec![],
+                Matched::Unmatched(_) | Matched::Trailing(_) | Matched::Operands(_) => break,
+            };
+
+            for (index, value) in matched {
+                if states.contains(ctx._get_opt_i32(index).state()) {
+                    ctx._set_value(index, value);
+                }
+            }
+            if stop {
+                break;
+            }
+        }
+        Ok(ctx)
+    }
+
+    /// Same as [`parse`](Ctx::parse), but classify the unmatched arguments with [`Unmatched`],
+    /// the arguments after `--` are not matched and the `--` itself is not returned
+    pub fn parse_classified(
+        &mut self,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<Vec<Unmatched>, Error> {
+        if !self.middlewares.is_empty() || self.limits.max_tokens.is_some() {
+            return self._parse_classified(&mut self._preprocess(args)?.into_iter());
+        }
+        self._parse_classified(args)
+    }
+
+    fn _parse_classified(
+        &mut self,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<Vec<Unmatched>, Error> {
+        let mut ret = vec![];
+        let mut unmatched = vec![];
+        let mut expansion = Expansion::default();
+        let mut total = 0;
+        let mut exclusive = None;
+
+        self._begin_parse()?;
+        while let Some(matched) = self._next_expanded(args, &mut expansion) {
+            let matched = matched?;
+            let stop = self._stop_at(&matched);
+
+            self._check_limits(&matched)?;
+            total += matched.len();
+            exclusive = exclusive.or_else(|| self._exclusive(&matched));
+            match matched {
+                Matched::Unmatched(arg) if arg == lex::TERMINATOR => {
+                    unmatched.extend(args.map(Unmatched::AfterTerminator));
+                }
+                Matched::Unmatched(arg) if self.looks_like_option(&arg) => {
+                    unmatched.push(Unmatched::LooksLikeOption(arg));
+                }
+                Matched::Unmatched(arg) => {
+                    unmatched.extend(self._capture_rest(arg).map(Unmatched::Positional));
+                }
+                Matched::Operands(operands) => {
+                    let mut operands = operands.into_iter();
+
+                    while let Some(arg) = operands.next() {
+                        if arg == lex::TERMINATOR {
+                            unmatched.extend(operands.by_ref().map(Unmatched::AfterTerminator));
+                        } else {
+                            unmatched.push(Unmatched::Positional(arg));
+                        }
+                    }
+                }
+                matched => self._apply(matched, &mut ret),
+            }
+            if stop {
+                break;
+            }
+        }
+        if let Some(s) = exclusive {
+            return self._check_exclusive(s, total).map(|_| unmatched);
+        }
+        self._check_rest()?;
+        self._apply_defaults()?;
+        self._check_required()?;
+        self._check_positionals(
+            unmatched
+                .iter()
+                .filter(|arg| !matches!(arg, Unmatched::LooksLikeOption(_)))
+                .count(),
+        )?;
+        Ok(unmatched)
+    }
+
+    fn _preprocess(&self, args: &mut impl Iterator<Item = String>) -> Result<Vec<String>, Error> {
+        let mut tokens: Vec<String> = match self.limits.max_tokens {
+            Some(max) => args.take(max + 1).collect(),
+            None => args.collect(),
+        };
+
+        self._check_tokens(tokens.len())?;
+        for middleware in self.middlewares.iter() {
+            tokens = middleware.process(tokens)?;
+        }
+        self._check_tokens(tokens.len())?;
+        Ok(tokens)
+    }
+
+    fn _check_tokens(&self, count: usize) -> Result<(), Error> {
+        match self.limits.max_tokens {
+            Some(max) if count > max => Err(Error::Limit(format!(
+                "Too many arguments, the limit is {}",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn _check_limits(&self, matched: &Matched<S>) -> Result<(), Error> {
+        let check = |s: &S, value: &Value, stored: usize| {
+            let values = match value {
+                Value::Str(value) => std::slice::from_ref(value),
+                Value::List(list) => list.as_slice(),
+                _ => &[],
+            };
+
+            match (self.limits.max_value_len, self.limits.max_values) {
+                (Some(max), _) if values.iter().any(|value| value.len() > max) => Err(
+                    Error::Limit(format!("Value is too long, the limit is {}: {:?}", max, s)),
+                ),
+                (_, Some(max)) if stored + values.len() > max => Err(Error::Limit(format!(
+                    "Too many values, the limit is {}: {:?}",
+                    max, s
+                ))),
+                _ => Ok(()),
+            }
+        };
+        let check_opt = |index: i32, value: &Value| {
+            let opt_keeper = self._get_opt_i32(index);
+            let stored = if opt_keeper.multiple || opt_keeper.delimiter.is_some() {
+                opt_keeper.value.as_list().len()
+            } else {
+                0
+            };
+
+            check(opt_keeper.state(), value, stored)
+        };
+        let check_args = |args: &[String]| {
+            if let Some(max) = self
+                .limits
+                .max_value_len
+                .filter(|max| args.iter().any(|arg| arg.len() > *max))
+            {
+                return Err(Error::Limit(format!(
+                    "Argument is too long, the limit is {}",
+                    max
+                )));
+            }
+            match (&self.positional_rest, self.limits.max_values) {
+                (Some((s, stored, _)), Some(max)) => {
+                    let captured = args
+                        .iter()
+                        .filter(|arg| *arg != lex::TERMINATOR && !self.looks_like_option(arg))
+                        .count();
+
+                    if stored.as_list().len() + captured > max {
+                        Err(Error::Limit(format!(
+                            "Too many values, the limit is {}: {:?}",
+                            max, s
+                        )))
+                    } else {
+                        Ok(())
+                    }
+                }
+                _ => Ok(()),
+            }
+        };
+
+        match matched {
+            Matched::Opt(index, value) => check_opt(*index, value),
+            Matched::Cluster(cluster) => cluster
+                .iter()
+                .try_for_each(|(index, value)| check_opt(*index, value)),
+            Matched::Dynamic(s, value) => check(s, value, 0),
+            Matched::Trailing(value) => match &self.trailing {
+                Some((s, _)) => check(s, value, 0),
+                None => Ok(()),
+            },
+            Matched::Unmatched(arg) => check_args(std::slice::from_ref(arg)),
+            Matched::Operands(operands) => check_args(operands),
+        }
+    }
+
+    fn _next_expanded(
+        &self,
+        args: &mut impl Iterator<Item = String>,
+        expansion: &mut Expansion,
+    ) -> Option<Result<Matched<S>, Error>> {
+        loop {
+            let pending = &mut expansion.pending;
+            let tokens = &mut expansion.tokens;
+            let mut args = std::iter::from_fn(|| pending.pop().or_else(|| args.next()))
+                .inspect(|_| *tokens += 1);
+            let matched = self._next_match(&mut args)?;
+
+            // the expanded alias may exceed the limit checked before the parse
+            if let Err(e) = self._check_tokens(expansion.tokens) {
+                return Some(Err(e));
+            }
+
+            if let Ok(Matched::Unmatched(arg)) = &matched {
+                if !expansion.expanded && arg != lex::TERMINATOR && !self.looks_like_option(arg) {
+                    expansion.expanded = true;
+                    if let Some((_, tokens)) = self.expansions.iter().find(|(name, _)| name == arg)
+                    {
+                        expansion.pending.extend(tokens.iter().rev().cloned());
+                        continue;
+                    }
+                }
+            }
+            return Some(matched);
+        }
+    }
+
+    fn _begin_parse(&mut self) -> Result<(), Error> {
+        if self.parsed {
+            match self.parse_policy {
+                ParsePolicy::Accumulate => {}
+                ParsePolicy::Fresh => {
+                    self.reset();
+                }
+                ParsePolicy::ErrorIfParsed => {
+                    return Err(Error::Argument(String::from(
+                        "The arguments have been parsed",
+                    )));
+                }
+            }
+        }
+        self.parsed = true;
+        Ok(())
+    }
+
+    fn _stop_at(&self, matched: &Matched<S>) -> bool {
+        match matched {
+            Matched::Unmatched(arg) => {
+                self.stop_at_unmatched && arg != lex::TERMINATOR && !self.looks_like_option(arg)
+            }
+            _ => false,
+        }
+    }
+
+    fn _capture_rest(&mut self, arg: String) -> Option<String> {
+        if arg == lex::TERMINATOR || self.looks_like_option(&arg) {
+            return Some(arg);
+        }
+        match self.positional_rest.as_mut() {
+            Some((_, Value::List(list), _)) => {
+                list.push(arg);
+                None
+            }
+            _ => Some(arg),
+        }
+    }
+
+    fn _exclusive(&self, matched: &Matched<S>) -> Option<S> {
+        let exclusive = |index: &i32| {
+            let opt_keeper = self._get_opt_i32(*index);
+
+            opt_keeper.exclusive.then(|| opt_keeper.state().clone())
+        };
+
+        match matched {
+            Matched::Opt(index, _) => exclusive(index),
+            Matched::Cluster(cluster) => cluster.iter().find_map(|(index, _)| exclusive(index)),
+            _ => None,
+        }
+    }
+
+    fn _check_exclusive(&self, s: S, total: usize) -> Result<S, Error> {
+        if total > 1 {
+            Err(Error::Argument(format!(
+                "Option can not be used with other arguments: {:?}",
+                s
+            )))
+        } else {
+            Ok(s)
+        }
+    }
+
+    fn _check_rest(&self) -> Result<(), Error> {
+        match &self.positional_rest {
+            Some((state, value, at_least)) if value.as_list().len() < *at_least => {
+                Err(Error::Missing(format!(
+                    "Need at least {} positional arguments, found {}: {:?}",
+                    at_least,
+                    value.as_list().len(),
+                    state
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn _resolve_fallback(&self, mut s: S) -> S {
+        // limit the steps so a cycle of fallback can not loop forever
+        for _ in 0..self.opt_keeper_repo.len() {
+            match self._find_state(&s) {
+                Some(opt_keeper) if opt_keeper.is_unset() => match &opt_keeper.fallback {
+                    Some(fallback) => s = fallback.clone(),
+                    None => break,
+                },
+                _ => break,
+            }
+        }
+        s
+    }
+
+    fn _apply_defaults(&mut self) -> Result<(), Error> {
+        let mut defaults = vec![];
+
+        for (index, opt_keeper) in self.opt_keeper_repo.iter().enumerate() {
+            if opt_keeper.count > 0 || !self._is_active(opt_keeper) {
+                continue;
+            }
+            let env = opt_keeper
+                .env
+                .as_ref()
+                .and_then(|name| std::env::var(name.as_ref()).ok());
+
+            if let Some(env) = env {
+                defaults.push((index, self._default_value(index, env)?));
+                continue;
+            }
+            let default = opt_keeper
+                .default_if
+                .iter()
+                .find(|(other, when, _)| match when {
+                    Some(value) => self._value_is(other, value),
+                    None => self.get_count(other.clone()) > 0,
+                })
+                .map(|(_, _, default)| default.as_str())
+                .or(opt_keeper.default_value.as_deref());
+
+            if let Some(default) = default {
+                defaults.push((index, self._default_value(index, String::from(default))?));
+            }
+        }
+        for (index, value) in defaults {
+            self._store_value(index as i32, value);
+        }
+        Ok(())
+    }
+
+    /// Convert the default or environment `value` like the one matched from command line,
+    /// the delimiter and transforms of option are applied
+    fn _default_value(&self, index: usize, value: String) -> Result<Value, Error> {
+        let opt_keeper = &self.opt_keeper_repo[index];
+
+        if opt_keeper.opt.is_switch() {
+            Ok(Value::Bool(!matches!(value.as_str(), "" | "0" | "false")))
+        } else if opt_keeper.opt.is_raw() || opt_keeper.opt.is_pattern() {
+            self._transform(opt_keeper, Value::from(&opt_keeper.opt, value))
+        } else {
+            self._match_opt(index as i32, Some(value), &mut std::iter::empty())
+                .map(|(_, value)| value)
+        }
+    }
+
+    fn _check_required(&self) -> Result<(), Error> {
+        for opt_keeper in self.active().filter(|ok| ok.is_unset()) {
+            for (other, value) in opt_keeper.required_if.iter() {
+                if self._value_is(other, value) {
+                    return Err(Error::Missing(format!(
+                        "Option {:?} is required when {:?} is {:?}",
+                        opt_keeper.opt.get_state(),
+                        other,
+                        value
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn _value_is(&self, s: &S, value: &str) -> bool {
+        match self.get_value(s.clone()) {
+            Some(Value::Str(string)) => string == value,
+            Some(Value::List(list)) => list.iter().any(|string| string == value),
+            _ => false,
+        }
+    }
+
+    fn _check_positionals(&self, count: usize) -> Result<(), Error> {
+        match self.positionals {
+            Some((min, _)) if count < min => Err(Error::Missing(format!(
+                "Too few positional arguments, expect at least {}, found {}",
+                min, count
+            ))),
+            Some((_, Some(max))) if count > max => Err(Error::Argument(format!(
+                "Too many positional arguments, expect at most {}, found {}",
+                max, count
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn _apply(&mut self, matched: Matched<S>, ret: &mut Vec<String>) {
+        match matched {
+            Matched::Opt(index, value) => {
+                self._set_value(index, value);
+            }
+            Matched::Cluster(matched) => {
+                for (index, value) in matched {
+                    self._set_value(index, value);
+                }
+            }
+            Matched::Trailing(value) => {
+                if let Some((_, trailing)) = self.trailing.as_mut() {
+                    *trailing = value;
+                }
+            }
+            Matched::Dynamic(state, value) => {
+                match self.dynamic.iter_mut().find(|(exist, _)| *exist == state) {
+                    Some((_, exist)) => *exist = value,
+                    None => self.dynamic.push((state, value)),
+                }
+            }
+            Matched::Unmatched(arg) => {
+                ret.extend(self._capture_rest(arg));
+            }
+            Matched::Operands(operands) => {
+                for arg in operands {
+                    ret.extend(self._capture_rest(arg));
+                }
+            }
+        }
+    }
+
+    fn _set_value(&mut self, index: i32, value: Value) {
+        if self._store_value(index, value) {
+            self._get_opt_mut_i32(index).count += 1;
+        }
+    }
+
+    /// Store `value` into the option, return false if the value is unset by [`EmptyValue::Unset`]
+    fn _store_value(&mut self, index: i32, value: Value) -> bool {
+        let opt_keeper = self._get_opt_mut_i32(index);
+
+        if value == Value::None && opt_keeper.empty_value == EmptyValue::Unset {
+            opt_keeper.value = value;
+            return false;
+        }
+        let collect = opt_keeper.multiple || opt_keeper.delimiter.is_some();
+
+        match (&mut opt_keeper.value, value) {
+            (Value::List(list), Value::Str(value)) if collect => list.push(value),
+            (Value::List(list), Value::List(value)) if collect => list.extend(value),
+            (stored, Value::Str(value)) if collect => *stored = Value::List(vec![value]),
+            (stored, value) => *stored = value,
+        }
+        true
+    }
+
+    /// Match the arguments lazily, yield an [`Event`] for every argument
+    /// instead of storing the value into the [`Ctx`], the [`middleware`](Ctx::middleware)s
+    /// and [`Limits::max_tokens`] need all the arguments so they are not applied.
+    /// A cluster yields one [`Event::Matched`] per flag, the trailing arguments
+    /// yield a single [`Event::Matched`] with a [`Value::List`], and the terminator
+    /// `--` yields an [`Event::Unmatched`] like any positional argument.
+    /// Defaults, environment values and aliases are not applied, and an error is
+    /// yielded as an `Err` item without stopping the iteration
+    pub fn parse_events<I>(&self, args: I) -> Events<'_, 'a, S, I>
+    where
+        I: Iterator<Item = String>,
+    {
+        Events {
+            ctx: self,
+            args,
+            pending: vec![],
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Matched<S> {
+    Opt(i32, Value),
+    Dynamic(S, Value),
+    Cluster(Vec<(i32, Value)>),
+    Trailing(Value),
+    Unmatched(String),
+    Operands(Vec<String>),
+}
+
+impl<S> Matched<S> {
+    fn len(&self) -> usize {
+        match self {
+            Matched::Cluster(cluster) => cluster.len(),
+            Matched::Operands(operands) => operands.len(),
+            _ => 1,
+        }
+    }
+}
+
+/// [`Outcome`] is returned by [`Ctx::parse_outcome`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<S> {
+    /// The parsing finished, hold the unmatched arguments
+    Continue(Vec<String>),
+    /// An exclusive option matched, the caller should handle it and exit
+    Exit(S),
+}
+
+/// [`Event`] is yield by [`Events`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<S> {
+    Matched(S, Value),
+    Unmatched(String),
+}
+
+/// [`Unmatched`] is an argument not matched by any option, returned by [`Ctx::parse_classified`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unmatched {
+    /// Argument not looking like an option
+    Positional(String),
+    /// Argument looking like an option, probably a typo or an unknown option
+    LooksLikeOption(String),
+    /// Argument after the `--`
+    AfterTerminator(String),
+}
+
+impl Unmatched {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Unmatched::Positional(arg)
+            | Unmatched::LooksLikeOption(arg)
+            | Unmatched::AfterTerminator(arg) => arg,
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        match self {
+            Unmatched::Positional(arg)
+            | Unmatched::LooksLikeOption(arg)
+            | Unmatched::AfterTerminator(arg) => arg,
+        }
+    }
+
+    /// Return the argument, or [`Error::UnknownOption`] if it looks like an option
+    pub fn into_positional(self) -> Result<String, Error> {
+        match self {
+            Unmatched::LooksLikeOption(arg) => {
+                Err(Error::UnknownOption(format!("Unknown option `{}`", arg)))
+            }
+            unmatched => Ok(unmatched.into_string()),
+        }
+    }
+}
+
+/// Lazy iterator created by [`Ctx::parse_events`]
+#[derive(Debug)]
+pub struct Events<'c, 'a, S, I>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    ctx: &'c Ctx<'a, S>,
+    args: I,
+    pending: Vec<Matched<S>>,
+}
+
+impl<'c, 'a, S, I> Iterator for Events<'c, 'a, S, I>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+    I: Iterator<Item = String>,
+{
+    type Item = Result<Event<S>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let matched = match self.pending.pop() {
+            Some(matched) => matched,
+            None => match self.ctx._next_match(&mut self.args)? {
+                Ok(Matched::Cluster(cluster)) => {
+                    self.pending = cluster
+                        .into_iter()
+                        .rev()
+                        .map(|(index, value)| Matched::Opt(index, value))
+                        .collect();
+                    return self.next();
+                }
+                Ok(Matched::Operands(operands)) => {
+                    self.pending = operands.into_iter().rev().map(Matched::Unmatched).collect();
+                    return self.next();
+                }
+                Ok(matched) => matched,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+
+        Some(Ok(match matched {
+            Matched::Opt(index, value) => {
+                Event::Matched(self.ctx._get_opt_i32(index).state().clone(), value)
+            }
+            Matched::Trailing(value) => {
+                let state = self.ctx.trailing.as_ref().map(|(state, _)| state.clone());
+
+                Event::Matched(state.unwrap_or_default(), value)
+            }
+            Matched::Dynamic(state, value) => Event::Matched(state, value),
+            Matched::Unmatched(arg) => Event::Unmatched(arg),
+            Matched::Cluster(_) | Matched::Operands(_) => unreachable!(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[allow(
+        clippy::enum_variant_names,
+        clippy::derivable_impls,
+        clippy::bool_assert_comparison
+    )]
+    fn opt_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        enum TestState {
+            CmdState,
+            BoolState,
+            OptionState,
+            HelpState,
+            UnknowState,
+        }
+
+        impl Default for TestState {
+            fn default() -> TestState {
+                TestState::UnknowState
+            }
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add(Arg::Bool("-bool".into(), TestState::BoolState));
+        ctx.add(Arg::Opt("-opt".into(), TestState::OptionState));
+        ctx.add(Arg::Bool("/?".into(), TestState::HelpState));
+        ctx.add(Arg::Bool("cmd".into(), TestState::CmdState));
+
+        let args: Vec<String> = ["cmd", "-bool", "-opt", "value", "/?"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()).is_ok(), true);
+        assert_eq!(
+            ctx.get_value(TestState::BoolState),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            ctx.get_value(TestState::HelpState),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(ctx.get_value(TestState::CmdState), Some(&Value::Bool(true)));
+        assert_eq!(
+            ctx.get_value(TestState::OptionState),
+            Some(&Value::Str(String::from("value")))
+        );
+    }
+
+    #[test]
+    fn cmd_alias_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Remove,
+            Rename,
+            Status,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_cmd("remove", TestState::Remove)
+            .alias(TestState::Remove, "rm")
+            .add_cmd("rename", TestState::Rename)
+            .add_cmd("status", TestState::Status)
+            .set_cmd_abbrev(true);
+
+        let args: Vec<String> = ["rm", "stat", "re"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("re")])
+        );
+        assert!(ctx.get_value_as_bool(TestState::Remove));
+        assert!(ctx.get_value_as_bool(TestState::Status));
+        assert!(!ctx.get_value_as_bool(TestState::Rename));
+
+        let cmd: Arg<TestState> = Arg::Cmd("remove".into(), TestState::Remove);
+        let bool: Arg<TestState> = Arg::Bool("--status".into(), TestState::Status);
+
+        assert!(cmd.is_cmd() && cmd.is_switch() && !cmd.is_bool());
+        assert!(!bool.is_cmd() && bool.is_switch() && bool.is_bool());
+    }
+
+    #[test]
+    fn event_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Bool,
+            Opt,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-b", TestState::Bool)
+            .add_str("-o", TestState::Opt);
+
+        let args: Vec<String> = ["-b", "pos", "-o", "value", "-o"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+        let mut events = ctx.parse_events(args.into_iter());
+
+        assert_eq!(
+            events.next(),
+            Some(Ok(Event::Matched(TestState::Bool, Value::Bool(true))))
+        );
+        assert_eq!(
+            events.next(),
+            Some(Ok(Event::Unmatched(String::from("pos"))))
+        );
+        assert_eq!(
+            events.next(),
+            Some(Ok(Event::Matched(
+                TestState::Opt,
+                Value::Str(String::from("value"))
+            )))
+        );
+        assert!(matches!(events.next(), Some(Err(_))));
+        assert_eq!(events.next(), None);
+        assert!(!ctx.get_value_as_bool(TestState::Bool));
+    }
+
+    #[test]
+    fn equals_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Color,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--color", TestState::Color)
+            .require_equals(TestState::Color, true)
+            .add_str("--output", TestState::Output);
+
+        let args: Vec<String> = ["--color=auto", "--output=a.txt", "b.txt"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("b.txt")])
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Color), "auto");
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a.txt");
+
+        let args: Vec<String> = ["--color", "auto"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn hyphen_value_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Offset,
+            Pattern,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--offset", TestState::Offset)
+            .add_str("--pattern", TestState::Pattern)
+            .allow_hyphen_values(TestState::Pattern, true)
+            .set_hyphen_value(HyphenValue::Number);
+
+        let args: Vec<String> = ["--offset", "-0.5", "--pattern", "-x"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Offset), "-0.5");
+        assert_eq!(ctx.get_value_as_str(TestState::Pattern), "-x");
+
+        let args: Vec<String> = ["--offset", "--pattern"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn trailing_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Run,
+            Child,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_cmd("run", TestState::Run)
+            .trailing(TestState::Child);
+
+        let args: Vec<String> = ["run", "pos", "--", "ls", "-l", "--"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("pos")])
+        );
+        assert_eq!(ctx.get_value_as_list(TestState::Child), ["ls", "-l", "--"]);
+
+        ctx.set_trailing_positional(true);
+
+        let args: Vec<String> = ["run", "ls", "-l"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert_eq!(ctx.get_value_as_list(TestState::Child), ["ls", "-l"]);
+    }
+
+    #[test]
+    fn raw_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Exec,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_raw("-exec", TestState::Exec, ";");
+
+        let args: Vec<String> = [".", "-exec", "rm", "{}", ";", "-print"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("."), String::from("-print")])
+        );
+        assert_eq!(ctx.get_value_as_list(TestState::Exec), ["rm", "{}"]);
+
+        let args: Vec<String> = ["-exec", "rm"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            With,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        assert!(ctx.add_regex("--with-(.+)", TestState::With).is_ok());
+        assert!(ctx.add_regex("--bad-(", TestState::With).is_err());
+
+        let args: Vec<String> = ["--with-ssl", "--without"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("--without")])
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::With), "ssl");
+    }
+
+    #[test]
+    fn pattern_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Extension,
+            Define,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_pattern("-X*", TestState::Extension)
+            .add_pattern("-D*=*", TestState::Define);
+
+        let args: Vec<String> = ["-Xmx2g", "-DKEY=VALUE", "-Y"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("-Y")])
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Extension), "mx2g");
+        assert_eq!(ctx.get_value_as_list(TestState::Define), ["KEY", "VALUE"]);
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn normalize_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Cafe,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("--caf\u{e9}", TestState::Cafe)
+            .set_normalize(true);
+
+        let args: Vec<String> = vec![String::from("--cafe\u{301}")];
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::Cafe));
+    }
+
+    #[test]
+    fn cluster_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            All,
+            Long,
+            Width,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-a", TestState::All)
+            .add_bool("-l", TestState::Long)
+            .add_str("-w", TestState::Width)
+            .set_cluster(true);
+
+        let args: Vec<String> = ["-alw80", "-ax"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("-ax")])
+        );
+        assert!(ctx.get_value_as_bool(TestState::All));
+        assert!(ctx.get_value_as_bool(TestState::Long));
+        assert_eq!(ctx.get_value_as_str(TestState::Width), "80");
+        assert_eq!(
+            ctx.parse(&mut vec![String::from("-"), String::from("file")].into_iter()),
+            Ok(vec![String::from("-"), String::from("file")])
+        );
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-é", TestState::All)
+            .add_bool("-🚀", TestState::Long)
+            .add_str("-w", TestState::Width)
+            .set_cluster(true);
+
+        let args: Vec<String> = ["-🚀éwñ"].iter().map(|data| String::from(*data)).collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::All));
+        assert!(ctx.get_value_as_bool(TestState::Long));
+        assert_eq!(ctx.get_value_as_str(TestState::Width), "ñ");
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("+a", TestState::All)
+            .add_bool("+l", TestState::Long)
+            .set_prefixes(vec!["+"])
+            .set_cluster(true);
+
+        let args: Vec<String> = ["+la"].iter().map(|data| String::from(*data)).collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::All));
+        assert!(ctx.get_value_as_bool(TestState::Long));
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn cluster_grapheme_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Acute,
+            Family,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-e\u{301}", TestState::Acute)
+            .add_bool(
+                "-\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+                TestState::Family,
+            )
+            .set_cluster(true);
+
+        let args: Vec<String> = ["-\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}e\u{301}"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::Acute));
+        assert!(ctx.get_value_as_bool(TestState::Family));
+    }
+
+    #[test]
+    fn owned_name_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Plugin(usize),
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        for (index, plugin) in ["foo", "bar"].iter().enumerate() {
+            ctx.add_str(format!("--{}-opt", plugin), TestState::Plugin(index));
+        }
+
+        let args: Vec<String> = ["--bar-opt", "value"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Plugin(1)), "value");
+    }
+
+    #[test]
+    fn count_test() {
+        use super::*;
+        use val::Count;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Debug,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_flag("-v", "--verbose", TestState::Verbose)
+            .add_bool("-d", TestState::Debug)
+            .set_cluster(true);
+
+        let args: Vec<String> = ["-vv", "--verbose"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as::<Count>(TestState::Verbose), Ok(Count(3)));
+        assert_eq!(ctx.get_value_as::<Count>(TestState::Debug), Ok(Count(0)));
+        assert_eq!(Count::parse(&Value::Bool(true)), Ok(Count(1)));
+    }
+
+    #[test]
+    fn delimiter_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Features,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--features", TestState::Features)
+            .value_delimiter(TestState::Features, ',');
+
+        let args: Vec<String> = ["--features", "a,b", "--features=c"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(
+            ctx.get_value_as_list(TestState::Features),
+            &[String::from("a"), String::from("b"), String::from("c")]
+        );
+        assert_eq!(
+            ctx.get_value_as::<Vec<String>>(TestState::Features)
+                .map(|list| list.len()),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn map_value_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Color,
+            Name,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--color", TestState::Color)
+            .trim(TestState::Color)
+            .lowercase(TestState::Color)
+            .add_str("--name", TestState::Name)
+            .map_value(TestState::Name, |value| value.replace('-', "_"));
+
+        let args: Vec<String> = ["--color", " Always ", "--name=a-b"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Color), "always");
+        assert_eq!(ctx.get_value_as_str(TestState::Name), "a_b");
+    }
+
+    #[test]
+    fn value_ref_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Name,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--name", TestState::Name);
+        assert!(ctx
+            .parse(&mut vec![String::from("--name=cute")].into_iter())
+            .is_ok());
+        assert_eq!(
+            ctx.get_value_ref::<Cow<str>>(TestState::Name),
+            Ok(Cow::Borrowed("cute"))
+        );
+        assert_eq!(
+            ctx.get_value_ref::<Option<&str>>(TestState::Unknow),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn value_with_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Jobs,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--jobs", TestState::Jobs);
+        assert!(ctx
+            .parse(&mut vec![String::from("--jobs=4")].into_iter())
+            .is_ok());
+        assert_eq!(
+            ctx.get_value_with(TestState::Jobs, |raw| raw.parse::<u8>()),
+            Ok(4)
+        );
+        assert!(ctx
+            .get_value_with(TestState::Jobs, |raw| raw.parse::<bool>())
+            .is_err());
+        assert!(ctx
+            .get_value_with(TestState::Unknow, |raw| Ok::<_, String>(raw.len()))
+            .is_err());
+    }
+
+    #[test]
+    fn multiple_test() {
+        use super::*;
+        use std::collections::{BTreeSet, HashSet};
+        use val::{First, Last, Unique};
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Target,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--target", TestState::Target)
+            .multiple(TestState::Target, true);
+
+        let args: Vec<String> = [
+            "--target",
+            "b",
+            "--target",
+            "a",
+            "--target=b",
+            "--target",
+            "c",
+        ]
+        .iter()
+        .map(|data| String::from(*data))
+        .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_list(TestState::Target).len(), 4);
+        assert_eq!(
+            ctx.get_value_as::<BTreeSet<String>>(TestState::Target),
+            Ok([String::from("a"), String::from("b"), String::from("c")]
+                .iter()
+                .cloned()
+                .collect())
+        );
+        assert_eq!(
+            ctx.get_value_as::<HashSet<String>>(TestState::Target)
+                .map(|set| set.len()),
+            Ok(3)
+        );
+        assert!(ctx
+            .get_value_as::<Unique<HashSet<String>>>(TestState::Target)
+            .is_err());
+        assert!(ctx
+            .get_value_as::<Unique<BTreeSet<String>>>(TestState::Unknow)
+            .is_ok());
+        assert_eq!(
+            ctx.get_value_as::<First<String>>(TestState::Target),
+            Ok(First(String::from("b")))
+        );
+        assert_eq!(
+            ctx.get_value_as::<Last<String>>(TestState::Target),
+            Ok(Last(String::from("c")))
+        );
+        assert_eq!(
+            ctx.get_value_as::<Last<Option<String>>>(TestState::Unknow),
+            Ok(Last(None))
+        );
+    }
+
+    #[test]
+    fn meta_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Group(&'static str);
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::Output)
+            .meta(TestState::Output, Group("io"))
+            .meta(TestState::Output, 42_u32)
+            .meta(TestState::Output, Group("file"));
+
+        assert_eq!(
+            ctx.get_meta::<Group>(TestState::Output),
+            Some(&Group("file"))
+        );
+        assert_eq!(ctx.get_meta::<u32>(TestState::Output), Some(&42));
+        assert_eq!(ctx.get_meta::<i32>(TestState::Output), None);
+        assert_eq!(ctx.get_meta::<u32>(TestState::Unknow), None);
+    }
+
+    #[test]
+    fn classified_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("--verbose", TestState::Verbose);
+
+        let args: Vec<String> = ["a.txt", "--verbos", "--verbose", "--", "--verbose", "b"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse_classified(&mut args.into_iter()),
+            Ok(vec![
+                Unmatched::Positional(String::from("a.txt")),
+                Unmatched::LooksLikeOption(String::from("--verbos")),
+                Unmatched::AfterTerminator(String::from("--verbose")),
+                Unmatched::AfterTerminator(String::from("b")),
+            ])
+        );
+        assert_eq!(ctx.get_count(TestState::Verbose), 1);
+    }
+
+    #[test]
+    fn positionals_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            #[default]
+            Unknow,
+        }
+
+        let parse = |args: &[&str]| {
+            let mut ctx = Ctx::new();
+            let mut args = args.iter().map(|data| String::from(*data));
+
+            ctx.add_bool("-v", TestState::Verbose)
+                .expect_positionals(1..=2);
+            ctx.parse(&mut args).map(|ret| ret.len())
+        };
+
+        assert_eq!(parse(&["a", "-v", "b", "-x"]), Ok(3));
+        assert!(parse(&["-v"]).is_err());
+        assert!(parse(&["a", "b", "c"]).is_err());
+    }
+
+    #[test]
+    fn positional_rest_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Inputs,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("-o", TestState::Output)
+            .positional_rest(TestState::Inputs, 1);
+
+        let args: Vec<String> = ["a.c", "-o", "out", "b.c", "-x"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("-x")])
+        );
+        assert_eq!(
+            ctx.get_value_as_list(TestState::Inputs),
+            &[String::from("a.c"), String::from("b.c")]
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "out");
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("-o", TestState::Output)
+            .positional_rest(TestState::Inputs, 1);
+        assert!(ctx
+            .parse(&mut vec![String::from("-o"), String::from("out")].into_iter())
+            .is_err());
+    }
+
+    #[test]
+    fn posix_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-v", TestState::Verbose).set_posix(true);
+
+        let args: Vec<String> = ["-x", "ls", "-v", "--", "-l"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+        let events: Vec<Event<TestState>> = ctx
+            .parse_events(args.clone().into_iter())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(events.len(), 5);
+        assert!(events
+            .iter()
+            .all(|event| matches!(event, Event::Unmatched(_))));
+        assert_eq!(ctx.parse(&mut args.into_iter()).map(|ret| ret.len()), Ok(5));
+        assert!(!ctx.get_value_as_bool(TestState::Verbose));
+    }
+
+    #[test]
+    fn stop_at_unmatched_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            User,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("-u", TestState::User)
+            .set_stop_at_unmatched(true);
+
+        let mut args = ["-u", "root", "ls", "-u", "-l"]
+            .iter()
+            .map(|data| String::from(*data));
+
+        assert_eq!(ctx.parse(&mut args), Ok(vec![String::from("ls")]));
+        assert_eq!(ctx.get_value_as_str(TestState::User), "root");
+        assert_eq!(
+            args.collect::<Vec<String>>(),
+            vec![String::from("-u"), String::from("-l")]
+        );
+    }
+
+    #[test]
+    fn parse_policy_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let args = || {
+            ["-v", "-o", "a"]
+                .iter()
+                .map(|data| String::from(*data))
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-v", TestState::Verbose)
+            .add_str("-o", TestState::Output);
+        assert!(!ctx.parsed());
+        assert!(ctx.parse(&mut args()).is_ok());
+        assert!(ctx.parse(&mut args()).is_ok());
+        assert!(ctx.parsed());
+        assert_eq!(ctx.get_count(TestState::Verbose), 2);
+
+        ctx.set_parse_policy(ParsePolicy::Fresh);
+        assert!(ctx.parse(&mut vec![String::from("-v")].into_iter()).is_ok());
+        assert_eq!(ctx.get_count(TestState::Verbose), 1);
+        assert_eq!(ctx.get_value(TestState::Output), Some(&Value::None));
+
+        ctx.set_parse_policy(ParsePolicy::ErrorIfParsed);
+        assert!(ctx.parse(&mut args()).is_err());
+        ctx.reset();
+        assert!(ctx.parse(&mut args()).is_ok());
+    }
+
+    #[test]
+    fn snapshot_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Build,
+            Release,
+            Output,
+            Define(String),
+            #[default]
+            Unknow,
+        }
+
+        let args = |args: &[&str]| {
+            args.iter()
+                .map(|data| String::from(*data))
+                .collect::<Vec<String>>()
+                .into_iter()
+        };
+        let mut ctx = Ctx::new();
+
+        ctx.add_cmd("build", TestState::Build)
+            .add_bool("--release", TestState::Release)
+            .add_str("--output", TestState::Output)
+            .alias(TestState::Output, "-o")
+            .expand_alias("b", ["build", "--release"])
+            .catch_all(|arg| {
+                arg.strip_prefix("-D")
+                    .map(|define| (TestState::Define(String::from(define)), Value::Bool(true)))
+            })
+            .set_parse_policy(ParsePolicy::ErrorIfParsed);
+        assert!(ctx.parse(&mut args(&["build", "-o", "a"])).is_ok());
+
+        let mut snapshot = ctx.snapshot();
+
+        assert!(!snapshot.parsed());
+        assert_eq!(snapshot.len(), ctx.len());
+        assert!(!snapshot.get_value_as_bool(TestState::Build));
+        assert_eq!(snapshot.get_value(TestState::Output), Some(&Value::None));
+        assert_eq!(
+            snapshot.parse(&mut args(&["b", "-o", "b", "-DDEBUG"])),
+            Ok(vec![])
+        );
+        assert!(snapshot.get_value_as_bool(TestState::Build));
+        assert!(snapshot.get_value_as_bool(TestState::Release));
+        assert_eq!(snapshot.get_value_as_str(TestState::Output), "b");
+        assert!(snapshot.get_value_as_bool(TestState::Define(String::from("DEBUG"))));
+        assert!(snapshot.parse(&mut args(&["build"])).is_err());
+        assert!(!ctx.get_value_as_bool(TestState::Release));
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a");
+    }
+
+    #[test]
+    fn merge_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Output,
+            Quiet,
+            #[default]
+            Unknow,
+        }
+
+        let mut common = Ctx::new();
+        let mut ctx = Ctx::new();
+
+        common.add_flag("-v", "--verbose", TestState::Verbose);
+        ctx.add_str("-o", TestState::Output);
+        assert!(ctx.merge(common).is_ok());
+        assert_eq!(ctx.len(), 2);
+
+        let mut conflict = Ctx::new();
+
+        conflict
+            .add_bool("-q", TestState::Quiet)
+            .alias(TestState::Quiet, "-o");
+        assert!(ctx.merge(conflict).is_err());
+        assert_eq!(ctx.len(), 2);
+
+        let args: Vec<String> = ["-v", "-o", "a"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+    }
+
+    #[test]
+    fn catch_all_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Define(String),
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.catch_all(|arg| {
+            let define = arg.strip_prefix("-D")?;
+            let (key, value) = define.split_once('=').unwrap_or((define, "1"));
+
+            Some((
+                TestState::Define(String::from(key)),
+                Value::Str(String::from(value)),
+            ))
+        });
+
+        let args: Vec<String> = ["-DDEBUG", "-DLEVEL=3", "-x", "-DLEVEL=4"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("-x")])
+        );
+        assert_eq!(
+            ctx.get_value_as_str(TestState::Define(String::from("DEBUG"))),
+            "1"
+        );
+        assert_eq!(
+            ctx.get_value_as::<u8>(TestState::Define(String::from("LEVEL"))),
+            Ok(4)
+        );
+        assert!(!ctx.has(TestState::Define(String::from("OTHER"))));
+    }
+
+    #[test]
+    fn expand_alias_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Build,
+            Release,
+            Jobs,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_cmd("build", TestState::Build)
+            .add_bool("--release", TestState::Release)
+            .add_str("-j", TestState::Jobs)
+            .expand_alias("b", vec!["build", "--release"]);
+
+        let args: Vec<String> = ["-j", "4", "b", "b"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("b")])
+        );
+        assert!(ctx.get_value_as_bool(TestState::Build));
+        assert!(ctx.get_value_as_bool(TestState::Release));
+        assert_eq!(ctx.get_value_as_str(TestState::Jobs), "4");
+    }
+
+    #[test]
+    fn empty_value_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Name,
+            Output,
+            Color,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--name", TestState::Name)
+            .add_str("--output", TestState::Output)
+            .add_str("--color", TestState::Color)
+            .empty_value(TestState::Output, EmptyValue::Error)
+            .empty_value(TestState::Color, EmptyValue::Unset);
+
+        let args: Vec<String> = ["--name=", "--color=auto", "--color="]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(
+            ctx.get_value(TestState::Name),
+            Some(&Value::Str(String::new()))
+        );
+        assert_eq!(ctx.get_value(TestState::Color), Some(&Value::None));
+        assert_eq!(ctx.get_count(TestState::Color), 1);
+
+        let args: Vec<String> = ["--output="]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn required_if_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Mode,
+            Host,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--mode", TestState::Mode)
+            .add_str("--host", TestState::Host)
+            .required_if(TestState::Host, TestState::Mode, "remote")
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let parse = |ctx: &mut Ctx<TestState>, args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|data| String::from(*data)).collect();
+
+            ctx.parse(&mut args.into_iter())
+        };
+
+        assert!(parse(&mut ctx, &["--mode", "local"]).is_ok());
+        assert!(parse(&mut ctx, &["--mode", "remote", "--host", "example.com"]).is_ok());
+        assert_eq!(
+            parse(&mut ctx, &["--mode", "remote"]),
+            Err(Error::Missing(String::from(
+                "Option Host is required when Mode is \"remote\""
+            )))
+        );
+    }
+
+    #[test]
+    fn default_value_if_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            MachineReadable,
+            Format,
+            Mode,
+            Port,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("--machine-readable", TestState::MachineReadable)
+            .add_str("--format", TestState::Format)
+            .add_str("--mode", TestState::Mode)
+            .add_str("--port", TestState::Port)
+            .default_value_if(TestState::Format, TestState::MachineReadable, None, "json")
+            .default_value_if(TestState::Port, TestState::Mode, Some("remote"), "22")
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let parse = |ctx: &mut Ctx<TestState>, args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|data| String::from(*data)).collect();
+
+            ctx.parse(&mut args.into_iter())
+        };
+
+        assert!(parse(&mut ctx, &["--mode", "local"]).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Format), "");
+        assert_eq!(ctx.get_value_as_str(TestState::Port), "");
+
+        assert!(parse(&mut ctx, &["--machine-readable", "--mode", "remote"]).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Format), "json");
+        assert_eq!(ctx.get_value_as_str(TestState::Port), "22");
+        assert_eq!(ctx.get_count(TestState::Format), 0);
+
+        assert!(parse(&mut ctx, &["--machine-readable", "--format", "yaml"]).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Format), "yaml");
+    }
+
+    #[test]
+    fn default_value_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Color,
+            Quiet,
+            Features,
+            Name,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("--color", TestState::Color)
+            .default_value(TestState::Color, "false")
+            .add_bool("--quiet", TestState::Quiet)
+            .default_value_if(TestState::Quiet, TestState::Color, None, "0")
+            .add_str("--features", TestState::Features)
+            .value_delimiter(TestState::Features, ',')
+            .default_value(TestState::Features, "a,b")
+            .add_str("--name", TestState::Name)
+            .trim(TestState::Name)
+            .default_value(TestState::Name, " cute ")
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        assert!(ctx.parse(&mut std::iter::empty()).is_ok());
+        assert!(!ctx.get_value_as_bool(TestState::Color));
+        assert_eq!(
+            ctx.get_value_as_list(TestState::Features),
+            &[String::from("a"), String::from("b")]
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Name), "cute");
+        assert_eq!(ctx.get_count(TestState::Name), 0);
+
+        assert!(ctx
+            .parse(&mut vec![String::from("--color")].into_iter())
+            .is_ok());
+        assert!(ctx.get_value_as_bool(TestState::Color));
+        assert!(!ctx.get_value_as_bool(TestState::Quiet));
+    }
+
+    #[test]
+    fn fallback_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            GlobalOutput,
+            BuildOutput,
+            TestOutput,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::GlobalOutput)
+            .add_str("--build-output", TestState::BuildOutput)
+            .add_str("--test-output", TestState::TestOutput)
+            .fallback(TestState::BuildOutput, TestState::GlobalOutput)
+            .fallback(TestState::TestOutput, TestState::BuildOutput);
+
+        let args: Vec<String> = ["--output", "out", "--build-output", "build"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::GlobalOutput), "out");
+        assert_eq!(ctx.get_value_as_str(TestState::BuildOutput), "build");
+        assert_eq!(ctx.get_value_as_str(TestState::TestOutput), "build");
+
+        ctx.reset();
+        ctx.fallback(TestState::GlobalOutput, TestState::TestOutput);
+
+        let args: Vec<String> = ["--output", "out"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::TestOutput), "out");
+        assert_eq!(ctx.get_count(TestState::GlobalOutput), 1);
+        assert_eq!(ctx.get_count(TestState::TestOutput), 0);
+        ctx.reset();
+        assert_eq!(ctx.get_value(TestState::TestOutput), Some(&Value::None));
+    }
+
+    #[test]
+    fn exclusive_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Help,
+            Verbose,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-h", TestState::Help)
+            .add_bool("-v", TestState::Verbose)
+            .add_str("-o", TestState::Output)
+            .exclusive(TestState::Help, true)
+            .set_cluster(true)
+            .expect_positionals(1..)
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let args = |args: &[&str]| -> Vec<String> {
+            args.iter().map(|data| String::from(*data)).collect()
+        };
+
+        assert_eq!(ctx.parse(&mut args(&["-h"]).into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::Help));
+        assert_eq!(
+            ctx.parse(&mut args(&["-v", "-h"]).into_iter()),
+            Err(Error::Argument(String::from(
+                "Option can not be used with other arguments: Help"
+            )))
+        );
+        assert!(ctx.parse(&mut args(&["-vh"]).into_iter()).is_err());
+        assert!(ctx.parse(&mut args(&["-h", "file"]).into_iter()).is_err());
+        assert_eq!(
+            ctx.parse(&mut args(&["-v", "file"]).into_iter()),
+            Ok(vec![String::from("file")])
+        );
+        assert_eq!(
+            ctx.parse_outcome(&mut args(&["-o", "out", "-h", "file"]).into_iter()),
+            Ok(Outcome::Exit(TestState::Help))
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "out");
+        assert_eq!(
+            ctx.parse_outcome(&mut args(&["-v", "file"]).into_iter()),
+            Ok(Outcome::Continue(vec![String::from("file")]))
+        );
+    }
+
+    #[test]
+    fn limits_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Name,
+            Include,
+            Rest,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--name", TestState::Name)
+            .add_str("-I", TestState::Include)
+            .multiple(TestState::Include, true)
+            .set_limits(Limits {
+                max_tokens: Some(6),
+                max_value_len: Some(8),
+                max_values: Some(2),
+            })
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let args = |args: &[&str]| -> Vec<String> {
+            args.iter().map(|data| String::from(*data)).collect()
+        };
+
+        assert!(ctx
+            .parse(&mut args(&["--name", "cute", "-I", "a", "-I", "b"]).into_iter())
+            .is_ok());
+        assert_eq!(
+            ctx.parse(&mut args(&["a", "b", "c", "d", "e", "f", "g"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Too many arguments, the limit is 6"
+            )))
+        );
+        assert_eq!(
+            ctx.parse(&mut args(&["--name=something"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Value is too long, the limit is 8: Name"
+            )))
+        );
+        assert_eq!(
+            ctx.parse(&mut args(&["-I", "a", "-I", "b", "-I", "c"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Too many values, the limit is 2: Include"
+            )))
+        );
+
+        ctx.expand_alias("all", ["-I", "a", "-I", "b", "--name", "cute"]);
+        assert_eq!(
+            ctx.parse(&mut args(&["all"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Too many arguments, the limit is 6"
+            )))
+        );
+        assert_eq!(
+            ctx.parse(&mut args(&["input", "something"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Argument is too long, the limit is 8"
+            )))
+        );
+
+        ctx.positional_rest(TestState::Rest, 0).set_posix(true);
+        assert_eq!(ctx.parse(&mut args(&["a", "b"]).into_iter()), Ok(vec![]));
+        assert_eq!(
+            ctx.parse(&mut args(&["a", "b", "c"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Too many values, the limit is 2: Rest"
+            )))
+        );
+        assert_eq!(
+            ctx.parse(&mut args(&["a", "something"]).into_iter()),
+            Err(Error::Limit(String::from(
+                "Argument is too long, the limit is 8"
+            )))
+        );
+    }
+
+    #[test]
+    fn env_default_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            Color,
+            Debug,
+            Level,
+            Includes,
+            #[default]
+            Unknow,
+        }
+
+        std::env::set_var("CUTEOPT_TEST_OUTPUT", "env-out");
+        std::env::set_var("CUTEOPT_TEST_DEBUG", "0");
+        std::env::set_var("CUTEOPT_TEST_INCLUDES", " src, include ");
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::Output)
+            .add_str("--color", TestState::Color)
+            .add_bool("--debug", TestState::Debug)
+            .add_str("--level", TestState::Level)
+            .env(TestState::Output, "CUTEOPT_TEST_OUTPUT")
+            .default_value(TestState::Output, "./out")
+            .env(TestState::Color, "CUTEOPT_TEST_COLOR_NOT_SET")
+            .default_value(TestState::Color, "auto")
+            .env(TestState::Debug, "CUTEOPT_TEST_DEBUG")
+            .default_value(TestState::Level, "info")
+            .add_str("--include", TestState::Includes)
+            .value_delimiter(TestState::Includes, ',')
+            .trim(TestState::Includes)
+            .env(TestState::Includes, "CUTEOPT_TEST_INCLUDES")
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let parse = |ctx: &mut Ctx<TestState>, args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|data| String::from(*data)).collect();
+
+            ctx.parse(&mut args.into_iter())
+        };
+
+        assert!(parse(&mut ctx, &[]).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "env-out");
+        assert_eq!(ctx.get_value_as_str(TestState::Color), "auto");
+        assert!(!ctx.get_value_as_bool(TestState::Debug));
+        assert_eq!(ctx.get_value_as_str(TestState::Level), "info");
+        assert_eq!(
+            ctx.get_value_as_list(TestState::Includes),
+            &[String::from("src"), String::from("include")]
+        );
+        assert!(parse(&mut ctx, &["--output", "cli-out", "--level=warn"]).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "cli-out");
+        assert_eq!(ctx.get_value_as_str(TestState::Level), "warn");
+    }
+
+    #[test]
+    fn windows_cmdline_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::Output);
+
+        assert_eq!(
+            ctx.parse_windows_cmdline(r#"--output "C:\Program Files\app\\" "a b.txt""#),
+            Ok(vec![String::from("a b.txt")])
+        );
+        assert_eq!(
+            ctx.get_value_as_str(TestState::Output),
+            r"C:\Program Files\app\"
+        );
+    }
+
+    #[test]
+    fn slash_alias_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            Verbose,
+            Quiet,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::Output)
+            .add_bool("-v", TestState::Verbose)
+            .add_bool("/quiet", TestState::Quiet)
+            .set_slash_alias(true);
+
+        let args: Vec<String> = ["/output", "a.txt", "/v", "--quiet", "/", "/usr"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("/"), String::from("/usr")])
+        );
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a.txt");
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+        assert!(ctx.get_value_as_bool(TestState::Quiet));
+    }
+
+    #[test]
+    fn powershell_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Path,
+            PassThru,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("-Verbose", TestState::Verbose)
+            .add_str("-Path", TestState::Path)
+            .add_bool("-PassThru", TestState::PassThru)
+            .set_powershell(true)
+            .set_parse_policy(ParsePolicy::Fresh);
+
+        let args = |args: &[&str]| -> Vec<String> {
+            args.iter().map(|data| String::from(*data)).collect()
+        };
+
+        assert!(ctx
+            .parse(&mut args(&["-verb", "-path:C:\\a.txt", "-PassT:$false"]).into_iter())
+            .is_ok());
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+        assert_eq!(ctx.get_value_as_str(TestState::Path), "C:\\a.txt");
+        assert!(!ctx.get_value_as_bool(TestState::PassThru));
+        assert_eq!(
+            ctx.parse(&mut args(&["-Pa", "x"]).into_iter()),
+            Ok(args(&["-Pa", "x"]))
+        );
+        assert!(ctx
+            .parse(&mut args(&["-Verbose:maybe"]).into_iter())
+            .is_err());
+    }
+
+    #[test]
+    fn auto_short_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Output,
+            Overwrite,
+            Verbose,
+            Version,
+            Build,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--output", TestState::Output)
+            .add_bool("--overwrite", TestState::Overwrite)
+            .add_flag("-v", "--verbose", TestState::Verbose)
+            .add_bool("--version", TestState::Version)
+            .add_cmd("build", TestState::Build)
+            .set_auto_short(true);
+
+        assert_eq!(ctx.auto_short_of(&TestState::Output), Some("-o"));
+        assert_eq!(ctx.auto_short_of(&TestState::Overwrite), Some("-O"));
+        assert_eq!(ctx.auto_short_of(&TestState::Verbose), None);
+        assert_eq!(ctx.auto_short_of(&TestState::Version), Some("-V"));
+        assert_eq!(ctx.auto_short_of(&TestState::Build), None);
+        assert!(help::options(&ctx).contains("-o, --output <VALUE>"));
+        assert!(complete::bash(&ctx, "app")
+            .contains("\"--output -o --overwrite -O --verbose -v --version -V build\""));
+
+        let args: Vec<String> = ["-o", "a.txt", "-O", "-V"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a.txt");
+        assert!(ctx.get_value_as_bool(TestState::Overwrite));
+        assert!(ctx.get_value_as_bool(TestState::Version));
+    }
+
+    #[test]
+    fn profiles_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Port,
+            Connect,
+            Verbose,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_str("--port", TestState::Port)
+            .profiles(TestState::Port, ["server"])
+            .add_str("--connect", TestState::Connect)
+            .profiles(TestState::Connect, ["client"])
+            .add_bool("--verbose", TestState::Verbose)
+            .activate_profile("server");
+
+        assert_eq!(ctx.active_profile(), Some("server"));
+        assert!(help::options(&ctx).contains("--port"));
+        assert!(!help::options(&ctx).contains("--connect"));
+
+        let args: Vec<String> = ["--port", "80", "--verbose", "--connect", "host"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+        let mut args = args.into_iter();
+
+        assert!(ctx.parse(&mut args).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Port), "80");
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+        assert_eq!(ctx.get_value(TestState::Connect), Some(&Value::None));
+
+        ctx.reset().activate_profile("client");
+
+        let args: Vec<String> = ["--connect", "host", "--port", "80"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Connect), "host");
+        assert_eq!(ctx.get_value(TestState::Port), Some(&Value::None));
+    }
+
+    #[test]
+    fn prescan_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Config,
+            Verbose,
+            Output,
+            Jobs,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_arg("-c", "--config", TestState::Config)
+            .add_flag("-v", "--verbose", TestState::Verbose)
+            .add_str("--output", TestState::Output)
+            .add_str("--jobs", TestState::Jobs)
+            .set_cluster(true);
+
+        let args: Vec<String> = [
+            "--output",
+            "--config",
+            "--jobs=x",
+            "-vv",
+            "--unknown",
+            "-c",
+            "app.toml",
+            "--",
+            "--verbose",
+        ]
+        .iter()
+        .map(|data| String::from(*data))
+        .collect();
+        let early = ctx
+            .prescan(&args, [TestState::Config, TestState::Verbose])
+            .unwrap();
+
+        assert_eq!(early.get_value_as_str(TestState::Config), "app.toml");
+        assert_eq!(early.get_count(TestState::Verbose), 2);
+        assert_eq!(early.get_value(TestState::Output), Some(&Value::None));
+        assert_eq!(early.get_value(TestState::Jobs), Some(&Value::None));
+        assert_eq!(ctx.get_count(TestState::Verbose), 0);
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "--config");
+
+        #[derive(Debug)]
+        struct DefaultConfig;
+
+        impl Middleware for DefaultConfig {
+            fn process(&self, tokens: Vec<String>) -> Result<Vec<String>, Error> {
+                Ok(tokens
+                    .into_iter()
+                    .flat_map(|token| match token.as_str() {
+                        "@default" => vec![String::from("-c"), String::from("default.toml")],
+                        _ => vec![token],
+                    })
+                    .collect())
+            }
+        }
+
+        ctx.middleware(DefaultConfig);
+
+        let early = ctx
+            .prescan(["-v", "@default"], [TestState::Config])
+            .unwrap();
+
+        assert_eq!(early.get_value_as_str(TestState::Config), "default.toml");
+        assert_eq!(early.get_count(TestState::Verbose), 0);
+
+        ctx.expand_alias("dev", ["-c", "dev.toml"]);
+
+        let early = ctx
+            .prescan(["dev", "-v"], [TestState::Config, TestState::Verbose])
+            .unwrap();
+
+        assert_eq!(early.get_value_as_str(TestState::Config), "dev.toml");
+        assert!(early.get_value_as_bool(TestState::Verbose));
+
+        ctx.set_posix(true);
+
+        let early = ctx
+            .prescan(
+                ["-v", "file", "-c", "b.toml"],
+                [TestState::Config, TestState::Verbose],
+            )
+            .unwrap();
+
+        assert!(early.get_value_as_bool(TestState::Verbose));
+        assert_eq!(early.get_value(TestState::Config), Some(&Value::None));
+
+        ctx.set_posix(false).set_stop_at_unmatched(true);
+
+        let early = ctx
+            .prescan(["file", "-c", "c.toml"], [TestState::Config])
+            .unwrap();
+
+        assert_eq!(early.get_value(TestState::Config), Some(&Value::None));
+        assert_eq!(
+            ctx.prescan(["-v", "--config"], [TestState::Verbose])
+                .map(|_| ()),
+            ctx.snapshot()
+                .parse(&mut vec![String::from("-v"), String::from("--config")].into_iter())
+                .map(|_| ())
+        );
+        assert!(matches!(
+            ctx.prescan(["--config"], [TestState::Config]),
+            Err(Error::Missing(_))
+        ));
+    }
+
+    #[test]
+    fn short_long_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_flag("-v", "--verbose", TestState::Verbose).add_arg(
+            "-o",
+            "--output",
+            TestState::Output,
+        );
+
+        let args: Vec<String> = ["-v", "-o", "a.out"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a.out");
+
+        let args: Vec<String> = ["--verbose", "--output", "b.out"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(ctx.parse(&mut args.into_iter()), Ok(vec![]));
+        assert!(ctx.get_value_as_bool(TestState::Verbose));
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "b.out");
+    }
+
+    #[test]
+    fn prefixes_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Color,
+            Output,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("+color", TestState::Color)
+            .add_str("-o", TestState::Output)
+            .set_prefixes(vec!["-", "+"]);
+
+        assert!(ctx.looks_like_option("+color"));
+        assert!(ctx.looks_like_option("+x"));
+        assert!(!ctx.looks_like_option("+"));
+        assert!(!ctx.looks_like_option("/x"));
+
+        let args: Vec<String> = ["+color", "-o", "a.out", "file"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert_eq!(
+            ctx.parse(&mut args.into_iter()),
+            Ok(vec![String::from("file")])
+        );
+        assert!(ctx.get_value_as_bool(TestState::Color));
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "a.out");
+
+        ctx.set_prefixes(vec!["+"]);
+
+        assert!(!ctx.looks_like_option("-o"));
+        assert!(Ctx::<TestState>::new().looks_like_option("-o"));
+    }
+
+    #[test]
+    fn send_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            #[default]
+            Unknow,
+        }
+
+        fn is_send<T: Send>() {}
+
+        is_send::<Ctx<'static, TestState>>();
+
+        let mut ctx = Ctx::new();
+
+        ctx.add_bool("--verbose", TestState::Verbose)
+            .transform(TestState::Verbose, transform::Trim)
+            .middleware(middleware::ResponseFile)
+            .catch_all(|_| None);
+
+        let snapshot = ctx.snapshot();
+        let handle = std::thread::spawn(move || {
+            let mut ctx = snapshot;
+
+            ctx.parse(&mut vec![String::from("--verbose")].into_iter())
+                .map(|_| ctx.get_value_as_bool(TestState::Verbose))
+        });
+
+        assert_eq!(handle.join().unwrap(), Ok(true));
     }
 }
@@ -0,0 +1,172 @@
+//! Customize how an option match the command line argument.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::Value;
+//!
+//! #[derive(Debug)]
+//! struct IgnoreCase(&'static str);
+//!
+//! impl Matcher for IgnoreCase {
+//!     fn r#match(&self, arg: &str) -> Option<Value> {
+//!         if self.0.eq_ignore_ascii_case(arg) {
+//!             Some(Value::None)
+//!         } else {
+//!             None
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Help,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_bool("-help", ParseState::Help)
+//!     .matcher(ParseState::Help, IgnoreCase("-help"));
+//! ctx.parse(&mut vec![String::from("-HELP")].into_iter()).unwrap();
+//!
+//! assert!(ctx.get_value_as_bool(ParseState::Help));
+//! ```
+
+use std::fmt::Debug;
+
+use super::Value;
+
+/// [`Matcher`] decide whether an argument match the option
+pub trait Matcher: Debug + Send + Sync {
+    /// Return `None` if `arg` not match.
+    ///
+    /// For the [`Arg::Pattern`](super::Arg::Pattern) the returned value is stored directly,
+    /// for the other options a [`Value::Str`] is used as the value of option,
+    /// just like the `name=value` form.
+    fn r#match(&self, arg: &str) -> Option<Value>;
+}
+
+/// Simple glob [`Matcher`], `*` match any sequence and `?` match any character,
+/// the sequences matched by `*` are captured as value
+#[derive(Debug, Clone)]
+pub struct Glob(String);
+
+impl Glob {
+    pub fn new(pattern: &str) -> Self {
+        Glob(String::from(pattern))
+    }
+}
+
+impl Matcher for Glob {
+    fn r#match(&self, arg: &str) -> Option<Value> {
+        let mut groups = vec![];
+
+        if glob_match(&self.0, arg, &mut groups) {
+            Some(captured_value(arg, groups))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Matcher for regex::Regex {
+    fn r#match(&self, arg: &str) -> Option<Value> {
+        let captures = self.captures(arg)?;
+        let groups: Vec<String> = captures
+            .iter()
+            .skip(1)
+            .flatten()
+            .map(|group| String::from(group.as_str()))
+            .collect();
+
+        Some(captured_value(arg, groups))
+    }
+}
+
+fn captured_value(arg: &str, mut groups: Vec<String>) -> Value {
+    match groups.len() {
+        0 => Value::Str(String::from(arg)),
+        1 => Value::Str(groups.remove(0)),
+        _ => Value::List(groups),
+    }
+}
+
+fn glob_match(pattern: &str, arg: &str, groups: &mut Vec<String>) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<(usize, char)> = arg.char_indices().collect();
+    let offset = |index: usize| chars.get(index).map_or(arg.len(), |(offset, _)| *offset);
+    // the char range captured by each `*`, only the last one grow when backtracking
+    let mut captures: Vec<(usize, usize)> = vec![];
+    let mut star = None;
+    let (mut p, mut a) = (0, 0);
+
+    while a < chars.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                captures.push((a, a));
+                p += 1;
+                star = Some(p);
+            }
+            Some(&pattern_char) if pattern_char == '?' || pattern_char == chars[a].1 => {
+                p += 1;
+                a += 1;
+            }
+            _ => match (star, captures.last_mut()) {
+                (Some(star), Some(capture)) => {
+                    capture.1 += 1;
+                    p = star;
+                    a = capture.1;
+                }
+                _ => return false,
+            },
+        }
+    }
+    while pattern.get(p) == Some(&'*') {
+        captures.push((a, a));
+        p += 1;
+    }
+    if p != pattern.len() {
+        return false;
+    }
+    groups.extend(
+        captures
+            .into_iter()
+            .map(|(start, end)| String::from(&arg[offset(start)..offset(end)])),
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn glob_test() {
+        use super::*;
+
+        let glob = |pattern: &str, arg: &str| Glob::new(pattern).r#match(arg);
+
+        assert_eq!(
+            glob("--help", "--help"),
+            Some(Value::Str(String::from("--help")))
+        );
+        assert_eq!(glob("--help", "--hel"), None);
+        assert_eq!(glob("-D*", "-Dkey"), Some(Value::Str(String::from("key"))));
+        assert_eq!(glob("-D*", "-D"), Some(Value::Str(String::new())));
+        assert_eq!(glob("-?", "-é"), Some(Value::Str(String::from("-é"))));
+        assert_eq!(
+            glob("--*-*", "--with-a-b"),
+            Some(Value::List(vec![String::from("with"), String::from("a-b")]))
+        );
+        assert_eq!(
+            glob("*=*", "名前=値"),
+            Some(Value::List(vec![String::from("名前"), String::from("値")]))
+        );
+        assert_eq!(glob("-*x", "-abc"), None);
+        assert_eq!(glob(&"*a".repeat(32), &"a".repeat(31)), None);
+        assert_eq!(
+            glob(&format!("{}b", "a*".repeat(32)), &"a".repeat(200)),
+            None
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Rewrite the command line arguments before they are matched.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::middleware::Middleware;
+//!
+//! #[derive(Debug)]
+//! struct Lowercase;
+//!
+//! impl Middleware for Lowercase {
+//!     fn process(&self, tokens: Vec<String>) -> Result<Vec<String>, Error> {
+//!         Ok(tokens.into_iter().map(|token| token.to_lowercase()).collect())
+//!     }
+//! }
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_bool("--verbose", ParseState::Verbose).middleware(Lowercase);
+//! ctx.parse(&mut vec![String::from("--VERBOSE")].into_iter()).unwrap();
+//!
+//! assert!(ctx.get_value_as_bool(ParseState::Verbose));
+//! ```
+
+use std::fmt::Debug;
+
+use super::Error;
+
+/// [`Middleware`] process all the arguments before matching,
+/// the middlewares are called in the order they are added
+pub trait Middleware: Debug + Send + Sync {
+    fn process(&self, tokens: Vec<String>) -> Result<Vec<String>, Error>;
+}
+
+/// Replace the `@file` with the arguments read from `file`,
+/// the content is split like a shell, the nested `@file` and the `@file` after `--` are not expanded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResponseFile;
+
+impl Middleware for ResponseFile {
+    fn process(&self, tokens: Vec<String>) -> Result<Vec<String>, Error> {
+        let mut ret = Vec::with_capacity(tokens.len());
+        let mut tokens = tokens.into_iter();
+
+        for token in tokens.by_ref() {
+            if token == super::lex::TERMINATOR {
+                ret.push(token);
+                break;
+            }
+            match token.strip_prefix('@').filter(|path| !path.is_empty()) {
+                Some(path) => {
+                    let content = std::fs::read_to_string(path)
+                        .map_err(|e| format!("Can not read response file `{}`: {}", path, e))?;
+
+                    ret.extend(
+                        super::val::split_shell_words(&content)
+                            .map_err(|e| format!("Invalid response file `{}`: {}", path, e))?,
+                    );
+                }
+                None => ret.push(token),
+            }
+        }
+        ret.extend(tokens);
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn response_file_test() {
+        use super::*;
+
+        let file =
+            std::env::temp_dir().join(format!("cuteopt-response-file-test-{}", std::process::id()));
+        let tokens = |data: &[&str]| -> Vec<String> {
+            data.iter().map(|data| String::from(*data)).collect()
+        };
+
+        std::fs::write(&file, "-o 'a b.txt'\n--verbose\n").unwrap();
+
+        let at_file = format!("@{}", file.display());
+
+        assert_eq!(
+            ResponseFile.process(tokens(&["-x", &at_file, "@"])),
+            Ok(tokens(&["-x", "-o", "a b.txt", "--verbose", "@"]))
+        );
+        assert_eq!(
+            ResponseFile.process(tokens(&[&at_file, "--", &at_file, "@missing"])),
+            Ok(tokens(&[
+                "-o",
+                "a b.txt",
+                "--verbose",
+                "--",
+                &at_file,
+                "@missing"
+            ]))
+        );
+        std::fs::remove_file(&file).unwrap();
+        assert!(ResponseFile.process(tokens(&[&at_file])).is_err());
+    }
+}
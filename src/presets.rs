@@ -0,0 +1,140 @@
+//! Ready-made option bundles registered with the states of caller.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::presets::{self, ColorChoice};
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     Quiet,
+//!     Color,
+//!     DryRun,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//! let verbosity = presets::verbosity(&mut ctx, ParseState::Verbose, ParseState::Quiet);
+//!
+//! presets::color(&mut ctx, ParseState::Color);
+//! presets::dry_run(&mut ctx, ParseState::DryRun);
+//! ctx.parse(&mut vec!["-v", "--color=never", "-n"].into_iter().map(String::from)).unwrap();
+//!
+//! assert_eq!(verbosity.level(&ctx), 3);
+//! assert_eq!(ctx.get_value_as(ParseState::Color), Ok(ColorChoice::Never));
+//! assert!(ctx.get_value_as_bool(ParseState::DryRun));
+//! ```
+
+use super::complete::ValueHint;
+use super::val::{as_single, ValueParser};
+use super::verbosity::Verbosity;
+use super::{Ctx, Value};
+
+/// [`ColorChoice`] is the value of `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ValueParser for ColorChoice {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::None => Ok(ColorChoice::Auto),
+            value => match as_single(value)? {
+                "auto" => Ok(ColorChoice::Auto),
+                "always" => Ok(ColorChoice::Always),
+                "never" => Ok(ColorChoice::Never),
+                string => Err(format!(
+                    "Invalid color choice `{}`, expect `auto`, `always` or `never`",
+                    string
+                )),
+            },
+        }
+    }
+}
+
+/// Add the `-v/--verbose` and `-q/--quiet` switches, return the [`Verbosity`] reading them
+pub fn verbosity<S>(ctx: &mut Ctx<'_, S>, verbose: S, quiet: S) -> Verbosity<S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    let verbosity = Verbosity::new(verbose, quiet);
+
+    verbosity.register(ctx);
+    verbosity
+}
+
+/// Add the `--color <WHEN>` option, parse it with [`ColorChoice`]
+pub fn color<'a, 'c, S>(ctx: &'c mut Ctx<'a, S>, s: S) -> &'c mut Ctx<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    ctx.add_str("--color", s.clone())
+        .value_name(s.clone(), "WHEN")
+        .help(s.clone(), "When to use colors: auto, always or never")
+        .default_value(s, "auto")
+}
+
+/// Add the `-i/--input <FILE>` and `-o/--output <FILE>` options, `-` means stdin or stdout
+pub fn io_paths<'a, 'c, S>(ctx: &'c mut Ctx<'a, S>, input: S, output: S) -> &'c mut Ctx<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    ctx.add_arg("-i", "--input", input.clone())
+        .value_name(input.clone(), "FILE")
+        .help(input.clone(), "Read the input from FILE, `-` for stdin")
+        .hint(input, ValueHint::FilePath)
+        .add_arg("-o", "--output", output.clone())
+        .value_name(output.clone(), "FILE")
+        .help(output.clone(), "Write the output to FILE, `-` for stdout")
+        .hint(output, ValueHint::FilePath)
+}
+
+/// Add the `-n/--dry-run` switch
+pub fn dry_run<'a, 'c, S>(ctx: &'c mut Ctx<'a, S>, s: S) -> &'c mut Ctx<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    ctx.add_flag("-n", "--dry-run", s.clone())
+        .help(s, "Print what would be done without doing it")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn presets_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Input,
+            Output,
+            Color,
+            #[default]
+            Unknow,
+        }
+
+        let mut ctx = Ctx::new();
+
+        io_paths(&mut ctx, TestState::Input, TestState::Output);
+        color(&mut ctx, TestState::Color);
+
+        let args: Vec<String> = ["-i", "-", "--output", "out.txt"]
+            .iter()
+            .map(|data| String::from(*data))
+            .collect();
+
+        assert!(ctx.parse(&mut args.into_iter()).is_ok());
+        assert_eq!(ctx.get_value_as_str(TestState::Input), "-");
+        assert_eq!(ctx.get_value_as_str(TestState::Output), "out.txt");
+        assert_eq!(
+            ctx.get_value_as::<ColorChoice>(TestState::Color),
+            Ok(ColorChoice::Auto)
+        );
+        assert!(ColorChoice::parse(&Value::Str(String::from("sometimes"))).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Named groups of options that can be installed into a [`Ctx`].
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::registry::Registry;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     Port,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut registry = Registry::new();
+//!
+//! registry
+//!     .register("logging", |ctx| {
+//!         ctx.add_flag("-v", "--verbose", ParseState::Verbose);
+//!     })
+//!     .register("net", |ctx| {
+//!         ctx.add_str("--port", ParseState::Port);
+//!     });
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.install(&registry, ["logging", "net"].iter().copied()).unwrap();
+//!
+//! assert_eq!(ctx.len(), 2);
+//! ```
+
+use std::fmt::{Debug, Formatter};
+
+use super::Ctx;
+
+type Factory<'a, S> = Box<dyn Fn(&mut Ctx<'a, S>)>;
+
+/// [`Registry`] map the group name to the function adding the options of group
+pub struct Registry<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    factories: Vec<(String, Factory<'a, S>)>,
+}
+
+impl<'a, S> Registry<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    pub fn new() -> Self {
+        Registry { factories: vec![] }
+    }
+
+    /// Register the group `name`, replace the previous one with same name
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn(&mut Ctx<'a, S>) + 'static,
+    ) -> &mut Self {
+        let name = name.into();
+
+        self.factories.retain(|(exist, _)| *exist != name);
+        self.factories.push((name, Box::new(factory)));
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.iter().any(|(exist, _)| exist == name)
+    }
+
+    /// Return the names of group in the order they are registered
+    pub fn names(&self) -> Vec<&str> {
+        self.factories
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    fn get(&self, name: &str) -> Option<&Factory<'a, S>> {
+        self.factories
+            .iter()
+            .find(|(exist, _)| exist == name)
+            .map(|(_, factory)| factory)
+    }
+}
+
+impl<'a, S> Default for Registry<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<'a, S> Debug for Registry<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("names", &self.names())
+            .finish()
+    }
+}
+
+impl<'a, S> Ctx<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    /// Add the options of groups `names` from `registry`,
+    /// fail without any change if one of the groups is not registered
+    pub fn install<'n>(
+        &mut self,
+        registry: &Registry<'a, S>,
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> Result<&mut Self, String> {
+        let factories = names
+            .into_iter()
+            .map(|name| {
+                registry
+                    .get(name)
+                    .ok_or_else(|| format!("Option group not registered: {:?}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for factory in factories {
+            factory(self);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn registry_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Port,
+            Host,
+            #[default]
+            Unknow,
+        }
+
+        let mut registry = Registry::new();
+
+        registry
+            .register("logging", |ctx| {
+                ctx.add_bool("--verbose", TestState::Verbose);
+            })
+            .register("net", |ctx| {
+                ctx.add_str("--port", TestState::Port);
+            });
+
+        assert_eq!(registry.names(), vec!["logging", "net"]);
+
+        let mut ctx = Ctx::new();
+
+        assert!(ctx
+            .install(&registry, ["net", "cache"].iter().copied())
+            .is_err());
+        assert_eq!(ctx.len(), 0);
+
+        registry.register("logging", |ctx| {
+            ctx.add_str("--host", TestState::Host)
+                .add_bool("--verbose", TestState::Verbose);
+        });
+
+        assert_eq!(registry.names(), vec!["net", "logging"]);
+        assert!(registry.contains("logging"));
+        assert!(!registry.contains("cache"));
+        assert!(ctx.install(&registry, ["logging"].iter().copied()).is_ok());
+        assert_eq!(ctx.len(), 2);
+    }
+}
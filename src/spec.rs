@@ -0,0 +1,203 @@
+//! Build the [`Ctx`] from a json or toml description at runtime.
+//!
+//! ```
+//! # #[cfg(feature = "json")]
+//! # {
+//! use cuteopt::prelude::*;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Plugin(String),
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let spec = r#"[
+//!     { "name": "--verbose", "aliases": ["-v"], "help": "Print more information" },
+//!     { "name": "--level", "consume": true, "state": "level" }
+//! ]"#;
+//! let mut ctx = Ctx::from_spec(spec, |state| Some(ParseState::Plugin(String::from(state)))).unwrap();
+//!
+//! ctx.parse(&mut vec!["-v", "--level", "3"].into_iter().map(String::from)).unwrap();
+//!
+//! assert!(ctx.get_value_as_bool(ParseState::Plugin(String::from("--verbose"))));
+//! assert_eq!(ctx.get_value_as_str(ParseState::Plugin(String::from("level"))), "3");
+//! # }
+//! ```
+
+use serde::Deserialize;
+
+use super::{Ctx, Error};
+
+/// The description of one option, shared by the json and toml spec
+#[derive(Debug, Deserialize)]
+struct OptSpec {
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    consume: bool,
+    #[serde(default)]
+    cmd: bool,
+    help: Option<String>,
+    state: Option<String>,
+}
+
+/// The toml spec, the options are the `[[option]]` tables
+#[cfg(feature = "toml")]
+#[derive(Debug, Deserialize)]
+struct TomlSpec {
+    #[serde(default)]
+    option: Vec<OptSpec>,
+}
+
+impl<'a, S> Ctx<'a, S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    /// Create the [`Ctx`] from a json array of options, every option is an object with keys
+    ///
+    /// * `name`: the option name, required
+    /// * `aliases`: array of alternate names
+    /// * `consume`: true if the option take an argument, default is false
+    /// * `cmd`: true if it is a command, default is false, can not be used with `consume`
+    /// * `help`: the help message
+    /// * `state`: the key passed to `state` for creating the state, default is the `name`
+    #[cfg(feature = "json")]
+    pub fn from_spec(spec: &str, state: impl Fn(&str) -> Option<S>) -> Result<Self, Error> {
+        let options: Vec<OptSpec> = serde_json::from_str(spec)
+            .map_err(|e| Error::Config(format!("Invalid option spec: {}", e)))?;
+
+        Self::_from_spec(options, state)
+    }
+
+    /// Same as [`from_spec`](Ctx::from_spec), but the options are the `[[option]]` tables
+    /// of toml with the same keys
+    #[cfg(feature = "toml")]
+    pub fn from_toml_spec(spec: &str, state: impl Fn(&str) -> Option<S>) -> Result<Self, Error> {
+        let spec: TomlSpec = toml::from_str(spec)
+            .map_err(|e| Error::Config(format!("Invalid option spec: {}", e.message())))?;
+
+        Self::_from_spec(spec.option, state)
+    }
+
+    fn _from_spec(options: Vec<OptSpec>, state: impl Fn(&str) -> Option<S>) -> Result<Self, Error> {
+        let mut ctx = Ctx::new();
+
+        for option in options {
+            let key = option.state.as_ref().unwrap_or(&option.name).clone();
+            let s = state(&key).ok_or_else(|| {
+                Error::Config(format!("Invalid option spec: unknown state `{}`", key))
+            })?;
+
+            if ctx.has(s.clone()) {
+                return Err(Error::Config(format!(
+                    "Invalid option spec: duplicate state `{}`",
+                    key
+                )));
+            }
+            match (option.cmd, option.consume) {
+                (true, true) => {
+                    return Err(Error::Config(format!(
+                        "Invalid option spec: `cmd` can not be used with `consume`: {}",
+                        option.name
+                    )))
+                }
+                (true, false) => ctx.add_cmd(option.name, s.clone()),
+                (false, true) => ctx.add_str(option.name, s.clone()),
+                (false, false) => ctx.add_bool(option.name, s.clone()),
+            };
+            if let Some(help) = option.help {
+                ctx.help(s.clone(), help);
+            }
+            for alias in option.aliases {
+                ctx.alias(s.clone(), alias);
+            }
+        }
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    enum TestState {
+        Build,
+        Jobs,
+        #[default]
+        Unknow,
+    }
+
+    fn state(key: &str) -> Option<TestState> {
+        match key {
+            "build" => Some(TestState::Build),
+            "jobs" => Some(TestState::Jobs),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn spec_test() {
+        use super::*;
+
+        let ctx = Ctx::from_spec(
+            r#"[{ "name": "build", "cmd": true, "aliases": ["b"] },
+                { "name": "-j", "consume": true, "state": "jobs", "help": "Jobs" }]"#,
+            state,
+        )
+        .unwrap();
+
+        assert_eq!(ctx.len(), 2);
+        assert!(ctx.get(TestState::Build).unwrap().is_cmd());
+        assert_eq!(ctx.iter().nth(1).unwrap().help, "Jobs");
+        assert!(Ctx::from_spec(r#"{ "name": "build" }"#, state).is_err());
+        assert!(Ctx::from_spec(r#"[{ "name": "-x" }]"#, state).is_err());
+        assert!(Ctx::from_spec(r#"[{ "name": "build", "consume": 1 }]"#, state).is_err());
+        assert!(Ctx::from_spec(r#"[{ "name": "build" }, { "name": "build" }]"#, state).is_err());
+        assert_eq!(
+            Ctx::from_spec(
+                r#"[{ "name": "build", "cmd": true, "consume": true }]"#,
+                state
+            )
+            .map(|ctx| ctx.len()),
+            Err(Error::Config(String::from(
+                "Invalid option spec: `cmd` can not be used with `consume`: build"
+            )))
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_spec_test() {
+        use super::*;
+
+        let ctx = Ctx::from_toml_spec(
+            r#"
+            [[option]]
+            name = "build"
+            cmd = true
+            aliases = ["b"]
+
+            [[option]]
+            name = "-j"
+            consume = true
+            state = "jobs"
+            help = "Jobs"
+            "#,
+            state,
+        )
+        .unwrap();
+
+        assert_eq!(ctx.len(), 2);
+        assert!(ctx.get(TestState::Build).unwrap().is_cmd());
+        assert_eq!(ctx.iter().nth(1).unwrap().help, "Jobs");
+        assert!(Ctx::from_toml_spec("[[option]]\nname = \"-x\"", state).is_err());
+        assert!(Ctx::from_toml_spec("[[option]]\nname = \"build\"\nconsume = 1", state).is_err());
+        assert!(Ctx::from_toml_spec(
+            "[[option]]\nname = \"build\"\ncmd = true\nconsume = true",
+            state
+        )
+        .is_err());
+    }
+}
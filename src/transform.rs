@@ -0,0 +1,213 @@
+//! Rewrite the value of option before it is stored, such as expanding environment variables.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::transform::EnvExpand;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Output,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! std::env::set_var("CUTEOPT_DOC_DIR", "/tmp");
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_str("--output", ParseState::Output)
+//!     .transform(ParseState::Output, EnvExpand);
+//! ctx.parse(&mut vec!["--output", "${CUTEOPT_DOC_DIR}/a.txt"].into_iter().map(String::from))
+//!     .unwrap();
+//!
+//! assert_eq!(ctx.get_value_as_str(ParseState::Output), "/tmp/a.txt");
+//! ```
+
+use std::fmt::Debug;
+
+/// [`Transform`] rewrite every string value of the option,
+/// the transforms of an option are applied in the order they are added
+pub trait Transform: Debug + Send + Sync {
+    fn transform(&self, value: String) -> Result<String, String>;
+}
+
+/// Rewrite the value with a function
+#[derive(Debug, Clone, Copy)]
+pub struct MapValue(pub fn(&str) -> String);
+
+impl Transform for MapValue {
+    fn transform(&self, value: String) -> Result<String, String> {
+        Ok((self.0)(&value))
+    }
+}
+
+/// Remove the leading and trailing whitespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Trim;
+
+impl Transform for Trim {
+    fn transform(&self, value: String) -> Result<String, String> {
+        Ok(String::from(value.trim()))
+    }
+}
+
+/// Convert the value into lowercase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Lowercase;
+
+impl Transform for Lowercase {
+    fn transform(&self, value: String) -> Result<String, String> {
+        Ok(value.to_lowercase())
+    }
+}
+
+/// Expand `$VAR` and `${VAR}` with the environment variables, `$$` is a literal `$`,
+/// reference to undefined variable is an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EnvExpand;
+
+impl Transform for EnvExpand {
+    fn transform(&self, value: String) -> Result<String, String> {
+        expand_env(&value, |name| std::env::var(name).ok())
+    }
+}
+
+/// Expand the leading `~` and `~user` into home directory,
+/// `~user` is looked up in `/etc/passwd` and only supported on unix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TildeExpand;
+
+impl Transform for TildeExpand {
+    fn transform(&self, value: String) -> Result<String, String> {
+        expand_tilde(value, |user| match user {
+            "" => std::env::var("HOME")
+                .or_else(|_| std::env::var("USERPROFILE"))
+                .ok(),
+            user => user_home_dir(user),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn user_home_dir(user: &str) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+
+        match fields.as_slice() {
+            [name, _, _, _, _, home, ..] if *name == user => Some(String::from(*home)),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(not(unix))]
+fn user_home_dir(_: &str) -> Option<String> {
+    None
+}
+
+fn expand_tilde(value: String, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let rest = match value.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return Ok(value),
+    };
+    let end = rest.find(std::path::is_separator).unwrap_or(rest.len());
+    let user = &rest[..end];
+
+    match lookup(user) {
+        Some(home) => Ok(format!("{}{}", home, &rest[end..])),
+        None if user.is_empty() => Err(format!("Can not find home directory for `{}`", value)),
+        None => Err(format!("Unknown user `{}` in `{}`", user, value)),
+    }
+}
+
+fn expand_env(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut ret = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(index) = rest.find('$') {
+        ret.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let (name, len) = if let Some(braced) = rest.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => return Err(format!("Missing closing `}}` in `{}`", value)),
+            }
+        } else if rest.starts_with('$') {
+            ret.push('$');
+            rest = &rest[1..];
+            continue;
+        } else {
+            let end = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+
+            (&rest[..end], end)
+        };
+
+        if name.is_empty() || !name.chars().all(is_name) {
+            if len == 0 {
+                ret.push('$');
+                continue;
+            }
+            return Err(format!("Invalid variable name `{}` in `{}`", name, value));
+        }
+        match lookup(name) {
+            Some(var) => ret.push_str(&var),
+            None => {
+                return Err(format!(
+                    "Undefined environment variable `{}` in `{}`",
+                    name, value
+                ))
+            }
+        }
+        rest = &rest[len..];
+    }
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn env_expand_test() {
+        use super::*;
+
+        let lookup = |name: &str| match name {
+            "HOME" => Some(String::from("/home/a")),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+
+        assert_eq!(
+            expand_env("$HOME/${HOME}x$EMPTY", lookup),
+            Ok(String::from("/home/a//home/ax"))
+        );
+        assert_eq!(
+            expand_env("$$HOME $ 5$", lookup),
+            Ok(String::from("$HOME $ 5$"))
+        );
+        assert!(expand_env("$UNDEFINED", lookup).is_err());
+        assert!(expand_env("${HOME", lookup).is_err());
+        assert!(expand_env("${}", lookup).is_err());
+    }
+
+    #[test]
+    fn tilde_expand_test() {
+        use super::*;
+
+        let lookup = |user: &str| match user {
+            "" => Some(String::from("/home/a")),
+            "b" => Some(String::from("/home/b")),
+            _ => None,
+        };
+        let expand = |value: &str| expand_tilde(String::from(value), lookup);
+
+        assert_eq!(expand("~"), Ok(String::from("/home/a")));
+        assert_eq!(expand("~/x/y"), Ok(String::from("/home/a/x/y")));
+        assert_eq!(expand("~b/x"), Ok(String::from("/home/b/x")));
+        assert_eq!(expand("a/~/x"), Ok(String::from("a/~/x")));
+        assert!(expand("~c/x").is_err());
+    }
+}
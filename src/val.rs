@@ -0,0 +1,2546 @@
+//! Convert the [`Value`] of option into the type you want.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Count,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//!
+//! ctx.add_str("--count", ParseState::Count);
+//! ctx.parse(&mut vec!["--count", "42"].into_iter().map(String::from)).unwrap();
+//!
+//! assert_eq!(ctx.get_value_as::<u32>(ParseState::Count), Ok(42));
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
+use std::convert::TryInto;
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::hash::Hash;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::Value;
+
+/// [`ValueParser`] parse the [`Value`] of option into `Self`
+pub trait ValueParser: Sized {
+    fn parse(value: &Value) -> Result<Self, String>;
+
+    /// Parse with the number of times the option appeared, the count is ignored by default
+    fn parse_with_count(value: &Value, count: usize) -> Result<Self, String> {
+        let _ = count;
+
+        Self::parse(value)
+    }
+}
+
+/// [`ValueRefParser`] parse the [`Value`] into `Self` borrowing from the value
+pub trait ValueRefParser<'v>: Sized {
+    fn parse_ref(value: &'v Value) -> Result<Self, String>;
+}
+
+impl<'v> ValueRefParser<'v> for &'v str {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        as_single(value)
+    }
+}
+
+impl<'v> ValueRefParser<'v> for Cow<'v, str> {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        as_single(value).map(Cow::Borrowed)
+    }
+}
+
+impl<'v> ValueRefParser<'v> for &'v OsStr {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        as_single(value).map(OsStr::new)
+    }
+}
+
+impl<'v> ValueRefParser<'v> for &'v Path {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        as_single(value).map(Path::new)
+    }
+}
+
+/// The UTF-8 bytes of value, `Vec<u8>` is not available because it parse every item as `u8`
+impl<'v> ValueRefParser<'v> for &'v [u8] {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        as_single(value).map(str::as_bytes)
+    }
+}
+
+/// Return `None` if the option has no value
+impl<'v, T: ValueRefParser<'v>> ValueRefParser<'v> for Option<T> {
+    fn parse_ref(value: &'v Value) -> Result<Self, String> {
+        match value {
+            Value::None => Ok(None),
+            value => T::parse_ref(value).map(Some),
+        }
+    }
+}
+
+/// Return the string of [`Value::Str`], or an error describing why the value is not available
+pub fn as_single(value: &Value) -> Result<&str, String> {
+    match value {
+        Value::Str(string) => Ok(string),
+        Value::None => Err(String::from("Missing value of option")),
+        Value::Bool(_) => Err(String::from("Can not get value from a boolean option")),
+        Value::List(list) => Err(format!("Expect one value, found {} values", list.len())),
+    }
+}
+
+macro_rules! impl_for {
+    (integer: $($type:ty),*) => {
+        $(
+            impl ValueParser for $type {
+                fn parse(value: &Value) -> Result<Self, String> {
+                    parse_integer(as_single(value)?)
+                }
+            }
+        )*
+    };
+    ($($type:ty),*) => {
+        $(
+            impl ValueParser for $type {
+                fn parse(value: &Value) -> Result<Self, String> {
+                    let string = as_single(value)?;
+
+                    string
+                        .parse::<$type>()
+                        .map_err(|e| format!("Invalid value `{}`: {}", string, e))
+                }
+            }
+        )*
+    };
+}
+
+/// Parse the integer, accept the digit separators such as `1_000_000` and `1,000`
+/// with the `separators` feature, the `,` must separate groups of 3 digits,
+/// use [`LocaleNumber`] to accept the separators for a single option only
+fn parse_integer<T>(string: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    #[cfg(feature = "separators")]
+    let number = Some(string)
+        .filter(|string| check_groups(string, ','))
+        .and_then(|string| strip_separators(string, &['_', ',']))
+        .ok_or_else(|| format!("Invalid value `{}`: misplaced digit separator", string))?;
+    #[cfg(not(feature = "separators"))]
+    let number = string;
+
+    number
+        .parse::<T>()
+        .map_err(|e| format!("Invalid value `{}`: {}", string, e))
+}
+
+impl_for!(integer: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl_for!(f32, f64, char);
+
+impl_for!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr);
+
+/// Return true if the option is set
+impl ValueParser for bool {
+    fn parse(value: &Value) -> Result<Self, String> {
+        Ok(match value {
+            Value::Bool(boolean) => *boolean,
+            Value::None => false,
+            _ => true,
+        })
+    }
+}
+
+/// Number of times the option appeared, such as `3` for `-v -v -v`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Count(pub usize);
+
+impl From<Count> for usize {
+    fn from(count: Count) -> Self {
+        count.0
+    }
+}
+
+impl ValueParser for Count {
+    /// Without the count, a set value is counted as one occurrence
+    fn parse(value: &Value) -> Result<Self, String> {
+        Ok(Count(match value {
+            Value::List(list) => list.len(),
+            value => bool::parse(value)? as usize,
+        }))
+    }
+
+    fn parse_with_count(_: &Value, count: usize) -> Result<Self, String> {
+        Ok(Count(count))
+    }
+}
+
+/// Parse every item like [`Vec`], the duplicate items are removed
+impl<T: ValueParser + Eq + Hash> ValueParser for HashSet<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        Vec::<T>::parse(value).map(|list| list.into_iter().collect())
+    }
+}
+
+/// Parse every item like [`Vec`], the duplicate items are removed
+impl<T: ValueParser + Ord> ValueParser for BTreeSet<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        Vec::<T>::parse(value).map(|list| list.into_iter().collect())
+    }
+}
+
+/// Parse any type implementing [`FromStr`](std::str::FromStr), such as the types of other crates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Parsed<T>(pub T);
+
+impl<T> ValueParser for Parsed<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        string
+            .parse::<T>()
+            .map(Parsed)
+            .map_err(|e| format!("Invalid value `{}`: {}", string, e))
+    }
+}
+
+/// Return `T::default()` if the option has no value, such as `0` for the integers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DefaultIfMissing<T>(pub T);
+
+impl<T: ValueParser + Default> ValueParser for DefaultIfMissing<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::None => Ok(DefaultIfMissing(T::default())),
+            value => T::parse(value).map(DefaultIfMissing),
+        }
+    }
+}
+
+/// Integer in the inclusive range `MIN..=MAX`, such as `Bounded<u8, 1, 16>`
+///
+/// The const generic parameter can not have the type `T`, so the bounds are `i64`,
+/// the bound of `u64` or `u128` larger than `i64::MAX` and the floats are not supported,
+/// check them with [`Ctx::get_value_with`](crate::Ctx::get_value_with) instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Bounded<T, const MIN: i64, const MAX: i64>(pub T);
+
+impl<T, const MIN: i64, const MAX: i64> Bounded<T, MIN, MAX> {
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
+impl<T, const MIN: i64, const MAX: i64> ValueParser for Bounded<T, MIN, MAX>
+where
+    T: ValueParser + Copy + TryInto<i128> + std::fmt::Display,
+{
+    fn parse(value: &Value) -> Result<Self, String> {
+        let number = T::parse(value)?;
+        let in_range = match number.try_into() {
+            Ok(wide) => (MIN as i128..=MAX as i128).contains(&wide),
+            Err(_) => false,
+        };
+
+        if in_range {
+            Ok(Bounded(number))
+        } else {
+            Err(format!(
+                "Value `{}` out of range, expect {} to {}",
+                number, MIN, MAX
+            ))
+        }
+    }
+}
+
+/// Number written in the locale convention, such as `1,000.5`,
+/// use `LocaleNumber<f64, ',', '.'>` for `1.000,5`, the group separator must separate
+/// groups of 3 digits, the `_` is always accepted as separator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct LocaleNumber<T, const DECIMAL: char = '.', const GROUP: char = ','>(pub T);
+
+impl<T, const DECIMAL: char, const GROUP: char> LocaleNumber<T, DECIMAL, GROUP> {
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
+impl<T: ValueParser, const DECIMAL: char, const GROUP: char> ValueParser
+    for LocaleNumber<T, DECIMAL, GROUP>
+{
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let number = match string.split_once(DECIMAL) {
+            Some((integer, _)) if !check_groups(integer, GROUP) => None,
+            None if !check_groups(string, GROUP) => None,
+            Some((integer, fraction)) => {
+                strip_separators(integer, &[GROUP, '_']).and_then(|integer| {
+                    Some(format!(
+                        "{}.{}",
+                        integer,
+                        strip_separators(fraction, &['_'])?
+                    ))
+                })
+            }
+            None => strip_separators(string, &[GROUP, '_']),
+        };
+
+        number
+            .and_then(|number| T::parse(&Value::Str(number)).ok())
+            .map(LocaleNumber)
+            .ok_or_else(|| format!("Invalid number `{}`", string))
+    }
+}
+
+/// Return true if every `group` separator is followed by exactly 3 digits and
+/// the digits before the first one are no more than 3, such as `-12,345,678`
+pub(crate) fn check_groups(string: &str, group: char) -> bool {
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    let mut parts = string.split(group);
+    let head = parts.next().unwrap_or_default();
+    let head = head
+        .strip_prefix(|c: char| c == '-' || c == '+')
+        .unwrap_or(head);
+
+    !string.contains(group)
+        || ((1..=3).contains(&head.len())
+            && is_digits(head)
+            && parts.all(|part| part.len() == 3 && is_digits(part)))
+}
+
+/// Remove the `separators` between digits, return `None` if a separator is not between digits
+pub(crate) fn strip_separators(string: &str, separators: &[char]) -> Option<String> {
+    let mut number = String::with_capacity(string.len());
+    let mut chars = string.chars().peekable();
+    let mut prev_digit = false;
+
+    while let Some(c) = chars.next() {
+        if separators.contains(&c) {
+            if !prev_digit || !chars.peek().is_some_and(char::is_ascii_digit) {
+                return None;
+            }
+        } else {
+            number.push(c);
+        }
+        prev_digit = c.is_ascii_digit();
+    }
+    Some(number)
+}
+
+/// Pick the first item of [`Value::List`], such as the first occurrence of a [`multiple`](super::Ctx::multiple) option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct First<T>(pub T);
+
+impl<T: ValueParser> ValueParser for First<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::List(list) => pick_item(list.first()),
+            value => T::parse(value),
+        }
+        .map(First)
+    }
+}
+
+/// Pick the last item of [`Value::List`], same as the value stored without
+/// [`multiple`](super::Ctx::multiple) where the later occurrence overwrite the earlier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Last<T>(pub T);
+
+impl<T: ValueParser> ValueParser for Last<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::List(list) => pick_item(list.last()),
+            value => T::parse(value),
+        }
+        .map(Last)
+    }
+}
+
+fn pick_item<T: ValueParser>(item: Option<&String>) -> Result<T, String> {
+    match item {
+        Some(item) => T::parse(&Value::Str(item.clone())),
+        None => T::parse(&Value::None),
+    }
+}
+
+/// Set such as [`HashSet`] or [`BTreeSet`] that report an error for the duplicate items
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Unique<C>(pub C);
+
+macro_rules! impl_unique_for {
+    ($($set:ident: $($bound:path),*);*) => {
+        $(
+            impl<T: ValueParser $(+ $bound)*> ValueParser for Unique<$set<T>> {
+                fn parse(value: &Value) -> Result<Self, String> {
+                    let mut set = $set::new();
+
+                    for item in Vec::<String>::parse(value)? {
+                        if !set.insert(T::parse(&Value::Str(item.clone()))?) {
+                            return Err(format!("Duplicate value `{}`", item));
+                        }
+                    }
+                    Ok(Unique(set))
+                }
+            }
+        )*
+    };
+}
+
+impl_unique_for!(HashSet: Eq, Hash; BTreeSet: Ord);
+
+/// Owned version of the string, use [`ValueRefParser`] to borrow it from the [`Value`]
+impl ValueParser for Cow<'static, str> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        as_single(value).map(|string| Cow::Owned(String::from(string)))
+    }
+}
+
+impl ValueParser for String {
+    fn parse(value: &Value) -> Result<Self, String> {
+        as_single(value).map(String::from)
+    }
+}
+
+/// Convert the value for the APIs taking [`OsString`], the arguments are stored as [`String`],
+/// so the non UTF-8 arguments are not supported and nothing is preserved beyond UTF-8
+impl ValueParser for OsString {
+    fn parse(value: &Value) -> Result<Self, String> {
+        as_single(value).map(OsString::from)
+    }
+}
+
+impl ValueParser for PathBuf {
+    fn parse(value: &Value) -> Result<Self, String> {
+        as_single(value).map(PathBuf::from)
+    }
+}
+
+/// Return `None` if the option has no value
+impl<T: ValueParser> ValueParser for Option<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::None => Ok(None),
+            value => T::parse(value).map(Some),
+        }
+    }
+}
+
+/// Parse every item of [`Value::List`], a [`Value::Str`] is treated as one item list
+impl<T: ValueParser> ValueParser for Vec<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::None => Ok(vec![]),
+            Value::List(list) => list
+                .iter()
+                .map(|item| T::parse(&Value::Str(item.clone())))
+                .collect(),
+            value => T::parse(value).map(|item| vec![item]),
+        }
+    }
+}
+
+impl ValueParser for Value {
+    fn parse(value: &Value) -> Result<Self, String> {
+        Ok(value.clone())
+    }
+}
+
+/// Open the file read-only
+impl ValueParser for File {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let path = as_single(value)?;
+
+        File::open(path).map_err(|e| format!("Can not open file `{}`: {}", path, e))
+    }
+}
+
+/// Standard input if the value is `-`, otherwise the file opened from the path
+#[derive(Debug)]
+pub enum FileOrStdin {
+    Stdin(std::io::Stdin),
+    File(PathBuf, File),
+}
+
+impl FileOrStdin {
+    pub fn is_stdin(&self) -> bool {
+        matches!(self, FileOrStdin::Stdin(_))
+    }
+
+    /// Return the path of file, `None` for the standard input
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            FileOrStdin::Stdin(_) => None,
+            FileOrStdin::File(path, _) => Some(path),
+        }
+    }
+}
+
+impl std::io::Read for FileOrStdin {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            FileOrStdin::Stdin(stdin) => stdin.read(buf),
+            FileOrStdin::File(_, file) => file.read(buf),
+        }
+    }
+}
+
+impl ValueParser for FileOrStdin {
+    fn parse(value: &Value) -> Result<Self, String> {
+        match as_single(value)? {
+            "-" => Ok(FileOrStdin::Stdin(std::io::stdin())),
+            path => Ok(FileOrStdin::File(PathBuf::from(path), File::parse(value)?)),
+        }
+    }
+}
+
+/// A path must be an existing file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExistingFile(pub PathBuf);
+
+impl AsRef<Path> for ExistingFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl ValueParser for ExistingFile {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let path = PathBuf::parse(value)?;
+
+        if path.is_file() {
+            Ok(ExistingFile(path))
+        } else if path.exists() {
+            Err(format!("Path `{}` is not a file", path.display()))
+        } else {
+            Err(format!("File `{}` does not exist", path.display()))
+        }
+    }
+}
+
+/// A path must be an existing directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExistingDir(pub PathBuf);
+
+impl AsRef<Path> for ExistingDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl ValueParser for ExistingDir {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let path = PathBuf::parse(value)?;
+
+        if path.is_dir() {
+            Ok(ExistingDir(path))
+        } else if path.exists() {
+            Err(format!("Path `{}` is not a directory", path.display()))
+        } else {
+            Err(format!("Directory `{}` does not exist", path.display()))
+        }
+    }
+}
+
+/// A path can be created, its parent directory must exist and not be read-only
+///
+/// The check only look at [`Permissions::readonly`](std::fs::Permissions::readonly),
+/// on unix it is true only when nobody has the write permission, the owner, the
+/// ACLs and the read-only mounts are not considered, so creating the path may
+/// still fail, handle the error of creating it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatablePath(pub PathBuf);
+
+impl AsRef<Path> for CreatablePath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl ValueParser for CreatablePath {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let path = PathBuf::parse(value)?;
+        let parent = match path.parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) => parent,
+            None => return Err(format!("Path `{}` has no parent directory", path.display())),
+        };
+
+        match parent.metadata() {
+            Ok(metadata) if !metadata.is_dir() => Err(format!(
+                "Parent `{}` of path `{}` is not a directory",
+                parent.display(),
+                path.display()
+            )),
+            Ok(metadata) if metadata.permissions().readonly() => Err(format!(
+                "Parent directory `{}` of path `{}` is not writable",
+                parent.display(),
+                path.display()
+            )),
+            Ok(_) => Ok(CreatablePath(path)),
+            Err(e) => Err(format!(
+                "Parent directory `{}` of path `{}` is not available: {}",
+                parent.display(),
+                path.display(),
+                e
+            )),
+        }
+    }
+}
+
+/// Parse seconds such as `30` or `1.5`
+impl ValueParser for Duration {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let seconds = f64::parse(value)?;
+
+        Duration::try_from_secs_f64(seconds)
+            .map_err(|e| format!("Invalid duration `{}` seconds: {}", seconds, e))
+    }
+}
+
+/// Duration such as `2h 15m`, `1d6h`, `500ms` or clock notation `HH:MM:SS`,
+/// the supported units are `w`, `d`, `h`, `m`, `s`, `ms`, `us` and `ns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HumanDuration(pub Duration);
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl ValueParser for HumanDuration {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        if string.contains(':') {
+            return parse_clock(string)
+                .map(HumanDuration)
+                .ok_or_else(|| format!("Invalid duration `{}`, expect `HH:MM:SS`", string));
+        }
+        parse_human_duration(string)
+            .map(HumanDuration)
+            .ok_or_else(|| format!("Invalid duration `{}`, expect such as `2h 15m`", string))
+    }
+}
+
+/// Clock notation duration such as `01:30:05.250`, `30:05` or `5.25`,
+/// the fraction of second has at most 9 digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClockDuration(pub Duration);
+
+impl From<ClockDuration> for Duration {
+    fn from(duration: ClockDuration) -> Self {
+        duration.0
+    }
+}
+
+impl ValueParser for ClockDuration {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        parse_clock(string)
+            .map(ClockDuration)
+            .ok_or_else(|| format!("Invalid duration `{}`, expect `HH:MM:SS.mmm`", string))
+    }
+}
+
+fn parse_clock(string: &str) -> Option<Duration> {
+    let (clock, nanos) = match string.split_once('.') {
+        Some((clock, fraction)) => {
+            if fraction.is_empty()
+                || fraction.len() > 9
+                || !fraction.bytes().all(|b| b.is_ascii_digit())
+            {
+                return None;
+            }
+            (clock, format!("{:0<9}", fraction).parse::<u32>().ok()?)
+        }
+        None => (string, 0),
+    };
+    let parts: Vec<&str> = clock.split(':').collect();
+
+    if parts.len() > 3 || parts.iter().any(|part| part.is_empty()) {
+        return None;
+    }
+    let mut seconds: u64 = 0;
+
+    for (index, part) in parts.iter().enumerate() {
+        let number = part.parse::<u64>().ok()?;
+
+        if index > 0 && number >= 60 {
+            return None;
+        }
+        seconds = seconds.checked_mul(60)?.checked_add(number)?;
+    }
+    Some(Duration::new(seconds, nanos))
+}
+
+fn parse_human_duration(string: &str) -> Option<Duration> {
+    let mut total = Duration::from_secs(0);
+    let mut rest = string.trim_start();
+
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let number_end = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number = rest[..number_end].parse::<u64>().ok()?;
+
+        rest = rest[number_end..].trim_start();
+
+        let unit_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let duration = match &rest[..unit_end] {
+            "w" => Duration::from_secs(number.checked_mul(7 * 24 * 3600)?),
+            "d" => Duration::from_secs(number.checked_mul(24 * 3600)?),
+            "h" => Duration::from_secs(number.checked_mul(3600)?),
+            "m" => Duration::from_secs(number.checked_mul(60)?),
+            "s" => Duration::from_secs(number),
+            "ms" => Duration::from_millis(number),
+            "us" => Duration::from_micros(number),
+            "ns" => Duration::from_nanos(number),
+            _ => return None,
+        };
+
+        total = total.checked_add(duration)?;
+        rest = rest[unit_end..].trim_start();
+    }
+    Some(total)
+}
+
+/// IP network such as `10.0.0.0/8` or `fe80::/10`, a bare address is a single host network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpNet {
+    pub addr: IpAddr,
+    pub prefix: u8,
+}
+
+impl IpNet {
+    pub fn max_prefix(addr: &IpAddr) -> u8 {
+        match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        }
+    }
+
+    /// Return the address with the host bits cleared
+    pub fn network(&self) -> IpAddr {
+        match self.addr {
+            IpAddr::V4(addr) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+
+                IpAddr::V4(Ipv4Addr::from(u32::from(addr) & mask))
+            }
+            IpAddr::V6(addr) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+
+                IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask))
+            }
+        }
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        let net = IpNet {
+            addr: *addr,
+            prefix: self.prefix,
+        };
+
+        addr.is_ipv4() == self.addr.is_ipv4() && net.network() == self.network()
+    }
+}
+
+impl std::fmt::Display for IpNet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl ValueParser for IpNet {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let (addr, prefix) = match string.find('/') {
+            Some(index) => (&string[..index], Some(&string[index + 1..])),
+            None => (string, None),
+        };
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|e| format!("Invalid address of network `{}`: {}", string, e))?;
+        let max_prefix = IpNet::max_prefix(&addr);
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid prefix length of network `{}`, expect 0 to {}",
+                        string, max_prefix
+                    )
+                })?,
+            None => max_prefix,
+        };
+
+        Ok(IpNet { addr, prefix })
+    }
+}
+
+/// Color in form of `#RRGGBB`, `#RGB` or `rgb(r, g, b)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl From<Color> for (u8, u8, u8) {
+    fn from(color: Color) -> Self {
+        (color.r, color.g, color.b)
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl ValueParser for Color {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        parse_color(string).ok_or_else(|| {
+            format!(
+                "Invalid color `{}`, expect `#RRGGBB`, `#RGB` or `rgb(r, g, b)`",
+                string
+            )
+        })
+    }
+}
+
+fn parse_color(string: &str) -> Option<Color> {
+    if let Some(hex) = string.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digit = |index: usize, len: usize| u8::from_str_radix(&hex[index..index + len], 16);
+
+        return match hex.len() {
+            3 => Some(Color {
+                r: digit(0, 1).ok()? * 17,
+                g: digit(1, 1).ok()? * 17,
+                b: digit(2, 1).ok()? * 17,
+            }),
+            6 => Some(Color {
+                r: digit(0, 2).ok()?,
+                g: digit(2, 2).ok()?,
+                b: digit(4, 2).ok()?,
+            }),
+            _ => None,
+        };
+    }
+    let inner = string.strip_prefix("rgb(")?.strip_suffix(')')?;
+    let parts = inner
+        .split(',')
+        .map(|part| part.trim().parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    match parts.as_slice() {
+        [r, g, b] => Some(Color {
+            r: *r,
+            g: *g,
+            b: *b,
+        }),
+        _ => None,
+    }
+}
+
+/// Percentage such as `35%` or fraction such as `0.35`, both are stored as `0.35`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Percent(pub f64);
+
+impl From<Percent> for f64 {
+    fn from(percent: Percent) -> Self {
+        percent.0
+    }
+}
+
+impl ValueParser for Percent {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+        let (number, scale) = match string.strip_suffix('%') {
+            Some(number) => (number.trim_end(), 100.0),
+            None => (string, 1.0),
+        };
+
+        number
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(|number| Percent(number / scale))
+            .ok_or_else(|| format!("Invalid percentage `{}`, expect `35%` or `0.35`", string))
+    }
+}
+
+/// Ratio such as `16:9`, the denominator can not be zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    pub num: u32,
+    pub den: u32,
+}
+
+impl Ratio {
+    pub fn as_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl std::fmt::Display for Ratio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.num, self.den)
+    }
+}
+
+impl ValueParser for Ratio {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+        let ratio = string.split_once(':').and_then(|(num, den)| {
+            Some(Ratio {
+                num: num.trim().parse().ok()?,
+                den: den.trim().parse().ok().filter(|den| *den != 0)?,
+            })
+        });
+
+        ratio.ok_or_else(|| format!("Invalid ratio `{}`, expect such as `16:9`", string))
+    }
+}
+
+/// Split the value like a shell, such as `gcc -O2 "-DNAME=a b"`,
+/// single quotes keep everything, backslash escapes outside of single quotes
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ShellWords(pub Vec<String>);
+
+impl From<ShellWords> for Vec<String> {
+    fn from(words: ShellWords) -> Self {
+        words.0
+    }
+}
+
+impl ValueParser for ShellWords {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        split_shell_words(string)
+            .map(ShellWords)
+            .map_err(|e| format!("Invalid shell words `{}`: {}", string, e))
+    }
+}
+
+pub(crate) fn split_shell_words(string: &str) -> Result<Vec<String>, &'static str> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = string.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                words.extend(word.take());
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("missing closing single quote"),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("missing closing double quote"),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("missing closing double quote"),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => return Err("unexpected backslash at the end"),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Bytes decoded from base64, both the standard and the URL safe alphabet are accepted,
+/// the `=` padding is optional
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Base64(pub Vec<u8>);
+
+impl From<Base64> for Vec<u8> {
+    fn from(bytes: Base64) -> Self {
+        bytes.0
+    }
+}
+
+impl AsRef<[u8]> for Base64 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ValueParser for Base64 {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        decode_base64(string)
+            .map(Base64)
+            .map_err(|e| format!("Invalid base64 value `{}`: {}", string, e))
+    }
+}
+
+fn decode_base64(string: &str) -> Result<Vec<u8>, String> {
+    let data = string.trim_end_matches('=');
+
+    if string.len() - data.len() > 2 || (string.len() != data.len() && string.len() % 4 != 0) {
+        return Err(String::from("incorrect padding"));
+    }
+    if data.len() % 4 == 1 {
+        return Err(String::from("incorrect length"));
+    }
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for (offset, c) in data.char_indices() {
+        let sextet = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '+' | '-' => 62,
+            '/' | '_' => 63,
+            _ => return Err(format!("invalid character `{}` at offset {}", c, offset)),
+        };
+
+        buffer = (buffer << 6) | sextet;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Bytes in hex such as `deadbeef`, `0xdeadbeef` or `DE:AD:BE:EF`,
+/// the bytes can be separated by `:` or `-`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl From<HexBytes> for Vec<u8> {
+    fn from(bytes: HexBytes) -> Self {
+        bytes.0
+    }
+}
+
+impl AsRef<[u8]> for HexBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ValueParser for HexBytes {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        decode_hex(string)
+            .filter(|bytes| !bytes.is_empty())
+            .map(HexBytes)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid hex bytes `{}`, expect such as `deadbeef` or `DE:AD:BE:EF`",
+                    string
+                )
+            })
+    }
+}
+
+fn decode_hex(string: &str) -> Option<Vec<u8>> {
+    let hex = string
+        .strip_prefix("0x")
+        .or_else(|| string.strip_prefix("0X"))
+        .unwrap_or(string);
+    let byte = |digits: &str| {
+        if digits.len() == 2 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            u8::from_str_radix(digits, 16).ok()
+        } else {
+            None
+        }
+    };
+
+    match hex.chars().find(|c| *c == ':' || *c == '-') {
+        Some(separator) => hex.split(separator).map(byte).collect(),
+        None if hex.len() % 2 == 0 => (0..hex.len())
+            .step_by(2)
+            .map(|index| hex.get(index..index + 2).and_then(byte))
+            .collect(),
+        None => None,
+    }
+}
+
+/// Pair such as `key=value`, split at the first `SEP` and parse each side,
+/// use `Pair<u16, u16, ':'>` for `8080:80`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Pair<K, V, const SEP: char = '='>(pub K, pub V);
+
+impl<K, V, const SEP: char> From<Pair<K, V, SEP>> for (K, V) {
+    fn from(pair: Pair<K, V, SEP>) -> Self {
+        (pair.0, pair.1)
+    }
+}
+
+impl<K: ValueParser, V: ValueParser, const SEP: char> ValueParser for Pair<K, V, SEP> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let (key, value) = string
+            .split_once(SEP)
+            .ok_or_else(|| format!("Invalid pair `{}`, expect `KEY{}VALUE`", string, SEP))?;
+
+        Ok(Pair(
+            K::parse(&Value::Str(String::from(key)))?,
+            V::parse(&Value::Str(String::from(value)))?,
+        ))
+    }
+}
+
+/// [`CsvRow`] build `Self` from the fields of one csv row
+pub trait CsvRow: Sized {
+    fn from_fields(fields: Vec<String>) -> Result<Self, String>;
+}
+
+impl<T: ValueParser> CsvRow for Vec<T> {
+    fn from_fields(fields: Vec<String>) -> Result<Self, String> {
+        fields
+            .into_iter()
+            .map(|field| T::parse(&Value::Str(field)))
+            .collect()
+    }
+}
+
+macro_rules! impl_csv_row_for_tuple {
+    ($len:expr => $($type:ident),*) => {
+        impl<$($type: ValueParser),*> CsvRow for ($($type,)*) {
+            fn from_fields(fields: Vec<String>) -> Result<Self, String> {
+                if fields.len() != $len {
+                    return Err(format!("Expect {} columns, found {}", $len, fields.len()));
+                }
+                let mut fields = fields.into_iter();
+
+                Ok(($($type::parse(&Value::Str(fields.next().unwrap_or_default()))?,)*))
+            }
+        }
+    };
+}
+
+impl_csv_row_for_tuple!(1 => A);
+impl_csv_row_for_tuple!(2 => A, B);
+impl_csv_row_for_tuple!(3 => A, B, C);
+impl_csv_row_for_tuple!(4 => A, B, C, D);
+impl_csv_row_for_tuple!(5 => A, B, C, D, E);
+impl_csv_row_for_tuple!(6 => A, B, C, D, E, F);
+
+/// Comma separated row such as `1.0, 2.0, 3.0` parsed into a tuple or [`Vec`],
+/// field can be quoted with `"` and `""` is a literal quote inside quoted field
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Csv<T>(pub T);
+
+impl<T: CsvRow> ValueParser for Csv<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let fields = split_csv_row(string)
+            .ok_or_else(|| format!("Invalid csv row `{}`: missing closing quote", string))?;
+
+        T::from_fields(fields)
+            .map(Csv)
+            .map_err(|e| format!("Invalid csv row `{}`: {}", string, e))
+    }
+}
+
+fn split_csv_row(string: &str) -> Option<Vec<String>> {
+    let mut fields = vec![];
+    let mut chars = string.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut field = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    '"' => break,
+                    c => field.push(c),
+                }
+            }
+            while chars.next_if(|c| *c != ',').is_some() {}
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                field.push(c);
+            }
+            field.truncate(field.trim_end().len());
+        }
+        fields.push(field);
+        if chars.next().is_none() {
+            break Some(fields);
+        }
+    }
+}
+
+/// Media type such as `text/html; charset=utf-8`,
+/// the type, subtype and parameter names are lowercased
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mime {
+    pub kind: String,
+    pub subtype: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl Mime {
+    /// Return the `type/subtype` without parameters
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.kind, self.subtype)
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl std::fmt::Display for Mime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind, self.subtype)?;
+        for (name, value) in self.params.iter() {
+            if is_token(value) {
+                write!(f, "; {}={}", name, value)?;
+            } else {
+                write!(f, "; {}={:?}", name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ValueParser for Mime {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        parse_mime(string).ok_or_else(|| {
+            format!(
+                "Invalid media type `{}`, expect `type/subtype[; name=value]`",
+                string
+            )
+        })
+    }
+}
+
+/// Return true if `string` is a token of RFC 2045
+fn is_token(string: &str) -> bool {
+    !string.is_empty()
+        && string
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+fn parse_mime(string: &str) -> Option<Mime> {
+    let mut parts = string.split(';');
+    let (kind, subtype) = parts.next()?.trim().split_once('/')?;
+
+    if !is_token(kind) || !is_token(subtype) {
+        return None;
+    }
+    let mut params = vec![];
+
+    for param in parts {
+        let (name, value) = param.trim().split_once('=')?;
+        let value = match value.strip_prefix('"') {
+            Some(quoted) => quoted.strip_suffix('"')?,
+            None if is_token(value) => value,
+            None => return None,
+        };
+
+        if !is_token(name) {
+            return None;
+        }
+        params.push((name.to_ascii_lowercase(), String::from(value)));
+    }
+    Some(Mime {
+        kind: kind.to_ascii_lowercase(),
+        subtype: subtype.to_ascii_lowercase(),
+        params,
+    })
+}
+
+/// HTTP header such as `Content-Type: text/plain` or `Accept=*/*`,
+/// the value is trimmed and the case of name is kept
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+impl From<Header> for (String, String) {
+    fn from(header: Header) -> Self {
+        (header.name, header.value)
+    }
+}
+
+impl std::fmt::Display for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+impl ValueParser for Header {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let (name, value) = string
+            .split_once([':', '='])
+            .ok_or_else(|| format!("Invalid header `{}`, expect `Name: value`", string))?;
+        let name = name.trim();
+        let value = value.trim();
+
+        if !is_token(name) {
+            return Err(format!("Invalid header name `{}`", name));
+        }
+        if value.chars().any(|c| c.is_ascii_control() && c != '\t') {
+            return Err(format!("Invalid header value of `{}`", name));
+        }
+        Ok(Header {
+            name: String::from(name),
+            value: String::from(value),
+        })
+    }
+}
+
+/// Host and port such as `example.com:8080` or `[::1]:80`,
+/// the bare host use the port `PORT`, it is an error if `PORT` is `0`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostPort<const PORT: u16 = 0> {
+    pub host: String,
+    pub port: u16,
+}
+
+impl<const PORT: u16> From<HostPort<PORT>> for (String, u16) {
+    fn from(host_port: HostPort<PORT>) -> Self {
+        (host_port.host, host_port.port)
+    }
+}
+
+impl<const PORT: u16> std::fmt::Display for HostPort<PORT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl<const PORT: u16> ValueParser for HostPort<PORT> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+        let (host, port) = if let Some(rest) = string.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| format!("Invalid host `{}`, missing `]`", string))?;
+
+            if host.parse::<Ipv6Addr>().is_err() {
+                return Err(format!("Invalid IPv6 address `{}`", host));
+            }
+            match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if rest.is_empty() => (host, None),
+                None => return Err(format!("Invalid host `{}`", string)),
+            }
+        } else if string.parse::<Ipv6Addr>().is_ok() {
+            (string, None)
+        } else {
+            match string.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (string, None),
+            }
+        };
+
+        if !host.is_empty() && host.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            if host.parse::<Ipv4Addr>().is_err() {
+                return Err(format!("Invalid IPv4 address `{}`", host));
+            }
+        } else if !host.contains(':') && !is_hostname(host) {
+            return Err(format!("Invalid host name `{}`", host));
+        }
+        let port = match port {
+            Some(port) => port
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| format!("Invalid port `{}` of `{}`", port, string))?,
+            None if PORT != 0 => PORT,
+            None => return Err(format!("Missing port of `{}`, expect `HOST:PORT`", string)),
+        };
+
+        Ok(HostPort {
+            host: String::from(host),
+            port,
+        })
+    }
+}
+
+/// Return true if `string` is a valid host name of RFC 1123
+fn is_hostname(string: &str) -> bool {
+    let string = string.strip_suffix('.').unwrap_or(string);
+
+    !string.is_empty()
+        && string.len() <= 253
+        && string.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Email address such as `user@example.com`, only the shape `local@domain` is checked
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Email {
+    pub local: String,
+    pub domain: String,
+}
+
+impl std::fmt::Display for Email {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.local, self.domain)
+    }
+}
+
+impl ValueParser for Email {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+        let invalid = |reason: &str| format!("Invalid email `{}`: {}", string, reason);
+        let (local, domain) = match string.split('@').collect::<Vec<_>>().as_slice() {
+            [local, domain] => (*local, *domain),
+            [_] => return Err(invalid("missing `@`")),
+            _ => return Err(invalid("more than one `@`")),
+        };
+
+        if local.is_empty() {
+            return Err(invalid("missing the name before `@`"));
+        }
+        if local.starts_with('.')
+            || local.ends_with('.')
+            || local.contains("..")
+            || local.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(invalid("invalid name before `@`"));
+        }
+        if !domain.contains('.') || domain.ends_with('.') || !is_hostname(domain) {
+            return Err(invalid("invalid domain after `@`"));
+        }
+        Ok(Email {
+            local: String::from(local),
+            domain: domain.to_ascii_lowercase(),
+        })
+    }
+}
+
+#[cfg(feature = "url")]
+impl ValueParser for url::Url {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        url::Url::parse(string).map_err(|e| format!("Invalid url `{}`: {}", string, e))
+    }
+}
+
+/// The allowed schemes of [`SchemeUrl`]
+#[cfg(feature = "url")]
+pub trait UrlScheme {
+    const SCHEMES: &'static [&'static str];
+}
+
+/// Allow `http` and `https` url
+#[cfg(feature = "url")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http;
+
+#[cfg(feature = "url")]
+impl UrlScheme for Http {
+    const SCHEMES: &'static [&'static str] = &["http", "https"];
+}
+
+/// An [`url::Url`] whose scheme must be one of [`UrlScheme::SCHEMES`]
+#[cfg(feature = "url")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemeUrl<T: UrlScheme>(pub url::Url, std::marker::PhantomData<T>);
+
+#[cfg(feature = "url")]
+impl<T: UrlScheme> SchemeUrl<T> {
+    pub fn into_inner(self) -> url::Url {
+        self.0
+    }
+}
+
+#[cfg(feature = "url")]
+impl<T: UrlScheme> ValueParser for SchemeUrl<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let url = <url::Url as ValueParser>::parse(value)?;
+
+        if T::SCHEMES.contains(&url.scheme()) {
+            Ok(SchemeUrl(url, std::marker::PhantomData))
+        } else {
+            Err(format!(
+                "Invalid scheme `{}` of url `{}`, expect one of {:?}",
+                url.scheme(),
+                url,
+                T::SCHEMES
+            ))
+        }
+    }
+}
+
+/// Parse `YYYY-MM-DD` date
+#[cfg(feature = "time")]
+impl ValueParser for time::Date {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let format = time::format_description::parse_borrowed::<2>("[year]-[month]-[day]")
+            .map_err(|e| format!("Invalid date format: {}", e))?;
+
+        time::Date::parse(string, &format)
+            .map_err(|e| format!("Invalid date `{}`, expect `YYYY-MM-DD`: {}", string, e))
+    }
+}
+
+/// Parse `YYYY-MM-DDTHH:MM:SS` or `YYYY-MM-DD HH:MM:SS` without offset
+#[cfg(feature = "time")]
+impl ValueParser for time::PrimitiveDateTime {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+        let format = if string.contains('.') {
+            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"
+        } else {
+            "[year]-[month]-[day]T[hour]:[minute]:[second]"
+        };
+        let format = time::format_description::parse_borrowed::<2>(format)
+            .map_err(|e| format!("Invalid datetime format: {}", e))?;
+
+        time::PrimitiveDateTime::parse(&string.replacen(' ', "T", 1), &format).map_err(|e| {
+            format!(
+                "Invalid datetime `{}`, expect `YYYY-MM-DDTHH:MM:SS`: {}",
+                string, e
+            )
+        })
+    }
+}
+
+/// Parse RFC 3339 timestamp such as `2024-01-01T00:00:00Z`
+#[cfg(feature = "time")]
+impl ValueParser for time::OffsetDateTime {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        time::OffsetDateTime::parse(string, &time::format_description::well_known::Rfc3339)
+            .map_err(|e| format!("Invalid RFC 3339 timestamp `{}`: {}", string, e))
+    }
+}
+
+/// Point in time from RFC 3339 timestamp or Unix epoch,
+/// the epoch is in milliseconds if it is larger than `99_999_999_999` else in seconds
+#[cfg(feature = "time")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub std::time::SystemTime);
+
+#[cfg(feature = "time")]
+impl From<Timestamp> for std::time::SystemTime {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+#[cfg(feature = "time")]
+impl ValueParser for Timestamp {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        if let Ok(epoch) = string.parse::<i64>() {
+            let duration = if epoch.unsigned_abs() > 99_999_999_999 {
+                Duration::from_millis(epoch.unsigned_abs())
+            } else {
+                Duration::from_secs(epoch.unsigned_abs())
+            };
+            let time = if epoch < 0 {
+                std::time::UNIX_EPOCH.checked_sub(duration)
+            } else {
+                std::time::UNIX_EPOCH.checked_add(duration)
+            };
+
+            return time
+                .map(Timestamp)
+                .ok_or_else(|| format!("Timestamp `{}` out of range", string));
+        }
+        time::OffsetDateTime::parse(string, &time::format_description::well_known::Rfc3339)
+            .map(|datetime| Timestamp(datetime.into()))
+            .map_err(|_| {
+                format!(
+                    "Invalid timestamp `{}`, expect RFC 3339 or Unix epoch",
+                    string
+                )
+            })
+    }
+}
+
+#[cfg(feature = "semver")]
+impl ValueParser for semver::Version {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        semver::Version::parse(string.trim())
+            .map_err(|e| format!("Invalid version `{}`: {}", string, e))
+    }
+}
+
+#[cfg(feature = "semver")]
+impl ValueParser for semver::VersionReq {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        semver::VersionReq::parse(string.trim())
+            .map_err(|e| format!("Invalid version requirement `{}`: {}", string, e))
+    }
+}
+
+/// Compile the pattern, the error message of regex contains the position of the syntax error
+#[cfg(feature = "regex")]
+impl ValueParser for regex::Regex {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        regex::Regex::new(string).map_err(|e| format!("Invalid regex `{}`:\n{}", string, e))
+    }
+}
+
+#[cfg(feature = "log")]
+impl ValueParser for log::LevelFilter {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+        let filter = match string {
+            "0" => Ok(log::LevelFilter::Off),
+            "1" => Ok(log::LevelFilter::Error),
+            "2" => Ok(log::LevelFilter::Warn),
+            "3" => Ok(log::LevelFilter::Info),
+            "4" => Ok(log::LevelFilter::Debug),
+            "5" => Ok(log::LevelFilter::Trace),
+            _ => string.parse::<log::LevelFilter>(),
+        };
+
+        filter.map_err(|_| {
+            format!(
+                "Invalid log level `{}`, expect one of off, error, warn, info, debug, trace or 0 to 5",
+                string
+            )
+        })
+    }
+}
+
+#[cfg(feature = "log")]
+impl ValueParser for log::Level {
+    fn parse(value: &Value) -> Result<Self, String> {
+        log::LevelFilter::parse(value)?.to_level().ok_or_else(|| {
+            format!(
+                "Invalid log level `{}`, expect one of error, warn, info, debug, trace or 1 to 5",
+                as_single(value).unwrap_or_default()
+            )
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+impl ValueParser for serde_json::Value {
+    fn parse(value: &Value) -> Result<Self, String> {
+        Json::<serde_json::Value>::parse(value).map(|json| json.0)
+    }
+}
+
+/// Value deserialized from inline json such as `{"a":1}`
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> ValueParser for Json<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        serde_json::from_str(string)
+            .map(Json)
+            .map_err(|e| format!("Invalid json `{}`: {}", string, e))
+    }
+}
+
+/// Value deserialized from inline toml such as `name = "a"`
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Toml<T>(pub T);
+
+#[cfg(feature = "toml")]
+impl<T: serde::de::DeserializeOwned> ValueParser for Toml<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        toml::from_str(string)
+            .map(Toml)
+            .map_err(|e| format!("Invalid toml `{}`: {}", string, e.message()))
+    }
+}
+
+/// Value deserialized from inline yaml such as `{name: a}`
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Yaml<T>(pub T);
+
+#[cfg(feature = "yaml")]
+impl<T: serde::de::DeserializeOwned> ValueParser for Yaml<T> {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?;
+
+        serde_yaml::from_str(string)
+            .map(Yaml)
+            .map_err(|e| format!("Invalid yaml `{}`: {}", string, e))
+    }
+}
+
+/// Paths matched by a glob pattern such as `src/**/*.rs`, at least one path must match
+#[cfg(feature = "glob")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GlobPaths(pub Vec<PathBuf>);
+
+#[cfg(feature = "glob")]
+impl From<GlobPaths> for Vec<PathBuf> {
+    fn from(paths: GlobPaths) -> Self {
+        paths.0
+    }
+}
+
+#[cfg(feature = "glob")]
+impl ValueParser for GlobPaths {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let pattern = as_single(value)?;
+        let paths = glob::glob(pattern)
+            .map_err(|e| format!("Invalid glob pattern `{}`: {}", pattern, e))?
+            .collect::<Result<Vec<PathBuf>, _>>()
+            .map_err(|e| format!("Can not expand glob pattern `{}`: {}", pattern, e))?;
+
+        if paths.is_empty() {
+            Err(format!("No path matches the glob pattern `{}`", pattern))
+        } else {
+            Ok(GlobPaths(paths))
+        }
+    }
+}
+
+/// Time zone from IANA name such as `Europe/Berlin` or fixed offset such as `+02:00`
+#[cfg(feature = "tz")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeZone {
+    Named(chrono_tz::Tz),
+    Fixed(chrono::FixedOffset),
+}
+
+#[cfg(feature = "tz")]
+impl ValueParser for TimeZone {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let string = as_single(value)?.trim();
+
+        if string.starts_with(['+', '-']) {
+            return parse_offset(string)
+                .map(TimeZone::Fixed)
+                .ok_or_else(|| format!("Invalid offset `{}`, expect such as `+02:00`", string));
+        }
+        string
+            .parse::<chrono_tz::Tz>()
+            .map(TimeZone::Named)
+            .map_err(|_| format!("Unknown time zone `{}`", string))
+    }
+}
+
+/// Parse offset such as `+02:00`, `-0530` or `+02`
+#[cfg(feature = "tz")]
+fn parse_offset(string: &str) -> Option<chrono::FixedOffset> {
+    let (sign, rest) = match string.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    if !rest.is_ascii() {
+        return None;
+    }
+    let (hours, minutes) = match rest.len() {
+        2 => (rest, "00"),
+        4 => rest.split_at(2),
+        5 => rest.split_once(':')?,
+        _ => return None,
+    };
+
+    if !hours
+        .bytes()
+        .chain(minutes.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    fn value(string: &str) -> super::Value {
+        super::Value::Str(String::from(string))
+    }
+
+    #[test]
+    fn path_test() {
+        use super::*;
+
+        let dir = std::env::temp_dir();
+        let file = dir.join(format!("cuteopt-path-test-{}", std::process::id()));
+        let value = |path: &Path| Value::Str(String::from(path.to_str().unwrap()));
+
+        std::fs::write(&file, b"cute").unwrap();
+
+        assert!(File::parse(&value(&file)).is_ok());
+        assert!(ExistingFile::parse(&value(&file)).is_ok());
+        assert!(ExistingFile::parse(&value(&dir)).is_err());
+        assert!(ExistingDir::parse(&value(&dir)).is_ok());
+        assert!(ExistingDir::parse(&value(&file)).is_err());
+        assert!(CreatablePath::parse(&value(&dir.join("cuteopt-new-file"))).is_ok());
+        assert!(CreatablePath::parse(&value(&file.join("child"))).is_err());
+
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(File::parse(&value(&file)).is_err());
+        assert!(ExistingFile::parse(&value(&file)).is_err());
+        assert!(File::parse(&Value::None).is_err());
+    }
+
+    #[test]
+    fn file_or_stdin_test() {
+        use super::*;
+        use std::io::Read;
+
+        let file =
+            std::env::temp_dir().join(format!("cuteopt-file-or-stdin-test-{}", std::process::id()));
+        let value = |path: &Path| Value::Str(String::from(path.to_str().unwrap()));
+
+        std::fs::write(&file, b"cute").unwrap();
+
+        let mut input = FileOrStdin::parse(&value(&file)).unwrap();
+        let mut content = String::new();
+
+        input.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "cute");
+        assert_eq!(input.path(), Some(file.as_path()));
+        assert!(!input.is_stdin());
+        assert!(FileOrStdin::parse(&Value::Str(String::from("-")))
+            .unwrap()
+            .is_stdin());
+
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(FileOrStdin::parse(&value(&file)).is_err());
+    }
+
+    #[test]
+    fn os_string_test() {
+        use super::*;
+
+        let path = Path::new("cuteopt").join("os-string");
+        let value = Value::Str(String::from(path.to_str().unwrap()));
+
+        assert_eq!(<&Path>::parse_ref(&value), Ok(path.as_path()));
+        assert_eq!(
+            OsString::parse(&value),
+            Ok(OsString::from(path.as_os_str()))
+        );
+        assert_eq!(
+            <&[u8]>::parse_ref(&Value::Str(String::from("ab"))),
+            Ok(&b"ab"[..])
+        );
+        assert!(OsString::parse(&Value::None).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_test() {
+        use super::*;
+
+        assert!(<time::Date as ValueParser>::parse(&value("2024-01-31")).is_ok());
+        assert!(<time::Date as ValueParser>::parse(&value("2024-02-31")).is_err());
+        assert!(
+            <time::PrimitiveDateTime as ValueParser>::parse(&value("2024-01-01T08:30:00")).is_ok()
+        );
+        assert!(
+            <time::PrimitiveDateTime as ValueParser>::parse(&value("2024-01-01 08:30:00.5"))
+                .is_ok()
+        );
+        assert!(
+            <time::OffsetDateTime as ValueParser>::parse(&value("2024-01-01T00:00:00Z")).is_ok()
+        );
+        assert!(<time::OffsetDateTime as ValueParser>::parse(&value("2024-01-01")).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn timestamp_test() {
+        use super::*;
+
+        let timestamp =
+            |string: &str| Timestamp::parse(&value(string)).map(std::time::SystemTime::from);
+        let epoch = |millis: u64| std::time::UNIX_EPOCH + Duration::from_millis(millis);
+
+        assert_eq!(
+            timestamp("2024-01-01T00:00:00.5+01:00"),
+            Ok(epoch(1_704_063_600_500))
+        );
+        assert_eq!(timestamp("1704067200"), Ok(epoch(1_704_067_200_000)));
+        assert_eq!(timestamp("1704067200123"), Ok(epoch(1_704_067_200_123)));
+        assert_eq!(
+            timestamp("-60"),
+            Ok(std::time::UNIX_EPOCH - Duration::from_secs(60))
+        );
+        assert_eq!(
+            timestamp("99999999999"),
+            Ok(std::time::UNIX_EPOCH + Duration::from_secs(99_999_999_999))
+        );
+        assert_eq!(timestamp("100000000000"), Ok(epoch(100_000_000_000)));
+        assert_eq!(
+            timestamp("-100000000000"),
+            Ok(std::time::UNIX_EPOCH - Duration::from_millis(100_000_000_000))
+        );
+        assert!(timestamp("2024-01-01").is_err());
+        assert!(timestamp("").is_err());
+    }
+
+    #[test]
+    fn duration_test() {
+        use super::*;
+
+        let human = |string: &str| HumanDuration::parse(&value(string)).map(Duration::from);
+
+        assert_eq!(
+            Duration::parse(&value("1.5")),
+            Ok(Duration::from_millis(1500))
+        );
+        assert!(Duration::parse(&value("1e20")).is_err());
+        assert!(Duration::parse(&value("-1")).is_err());
+        assert!(Duration::parse(&value("inf")).is_err());
+        assert_eq!(human("2h 15m"), Ok(Duration::from_secs(8100)));
+        assert_eq!(human("1d6h"), Ok(Duration::from_secs(108_000)));
+        assert_eq!(human("1s 500ms"), Ok(Duration::from_millis(1500)));
+        assert_eq!(human("01:02:03"), Ok(Duration::from_secs(3723)));
+        assert!(human("2 hours").is_err());
+        assert!(human("1:60").is_err());
+        assert!(human("").is_err());
+        assert_eq!(human("01:02:03.5"), Ok(Duration::from_millis(3_723_500)));
+    }
+
+    #[test]
+    fn clock_duration_test() {
+        use super::*;
+
+        let clock = |string: &str| ClockDuration::parse(&value(string)).map(Duration::from);
+
+        assert_eq!(clock("01:30:05.250"), Ok(Duration::from_millis(5_405_250)));
+        assert_eq!(clock("30:05"), Ok(Duration::from_secs(1805)));
+        assert_eq!(clock("5.000000001"), Ok(Duration::new(5, 1)));
+        assert!(clock("1:2:3:4").is_err());
+        assert!(clock("00:61").is_err());
+        assert!(clock("5.").is_err());
+        assert!(clock("5.1234567890").is_err());
+    }
+
+    #[test]
+    fn ip_net_test() {
+        use super::*;
+
+        let net = IpNet::parse(&value("10.1.2.3/8")).unwrap();
+
+        assert_eq!(net.network(), "10.0.0.0".parse::<IpAddr>().unwrap());
+        assert!(net.contains(&"10.255.0.1".parse().unwrap()));
+        assert!(!net.contains(&"11.0.0.1".parse().unwrap()));
+        assert_eq!(IpNet::parse(&value("fe80::/10")).unwrap().prefix, 10);
+        assert_eq!(IpNet::parse(&value("127.0.0.1")).unwrap().prefix, 32);
+        assert!(IpNet::parse(&value("10.0.0.0/33")).is_err());
+        assert!(IpNet::parse(&value("10.0.0/8")).is_err());
+    }
+
+    #[test]
+    fn color_test() {
+        use super::*;
+
+        assert_eq!(
+            Color::parse(&value("#ff8000")),
+            Ok(Color {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(
+            Color::parse(&value("#F80")),
+            Ok(Color {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(
+            Color::parse(&value("rgb(1, 2, 3)")).map(<(u8, u8, u8)>::from),
+            Ok((1, 2, 3))
+        );
+        assert!(Color::parse(&value("#ff80")).is_err());
+        assert!(Color::parse(&value("rgb(1, 2, 256)")).is_err());
+        assert!(Color::parse(&value("red")).is_err());
+    }
+
+    #[test]
+    fn ratio_test() {
+        use super::*;
+
+        assert_eq!(Percent::parse(&value("35%")), Ok(Percent(0.35)));
+        assert_eq!(Percent::parse(&value("0.35")), Ok(Percent(0.35)));
+        assert!(Percent::parse(&value("%")).is_err());
+        assert!(Percent::parse(&value("inf")).is_err());
+        assert_eq!(Ratio::parse(&value("16:9")), Ok(Ratio { num: 16, den: 9 }));
+        assert!(Ratio::parse(&value("16:0")).is_err());
+        assert!(Ratio::parse(&value("16/9")).is_err());
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_test() {
+        use super::*;
+
+        assert_eq!(
+            log::LevelFilter::parse(&value("WARN")),
+            Ok(log::LevelFilter::Warn)
+        );
+        assert_eq!(
+            log::LevelFilter::parse(&value("0")),
+            Ok(log::LevelFilter::Off)
+        );
+        assert_eq!(log::Level::parse(&value("5")), Ok(log::Level::Trace));
+        assert!(log::Level::parse(&value("off")).is_err());
+        assert!(log::LevelFilter::parse(&value("6")).is_err());
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn semver_test() {
+        use super::*;
+
+        assert_eq!(
+            <semver::Version as ValueParser>::parse(&value(" 1.2.3 ")),
+            Ok(semver::Version::new(1, 2, 3))
+        );
+        assert!(<semver::Version as ValueParser>::parse(&value("1.2")).is_err());
+        assert_eq!(
+            <semver::VersionReq as ValueParser>::parse(&value(" >=1.2, <2 "))
+                .map(|req| req.to_string()),
+            Ok(String::from(">=1.2, <2"))
+        );
+        assert!(<semver::VersionReq as ValueParser>::parse(&value(">=x")).is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_test() {
+        use super::*;
+
+        assert_eq!(
+            <regex::Regex as ValueParser>::parse(&value("^v[0-9]+$"))
+                .map(|regex| regex.is_match("v12")),
+            Ok(true)
+        );
+        assert!(<regex::Regex as ValueParser>::parse(&value("v[0-9"))
+            .unwrap_err()
+            .starts_with("Invalid regex `v[0-9`"));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn url_test() {
+        use super::*;
+
+        assert_eq!(
+            <url::Url as ValueParser>::parse(&value("https://example.com/a?b=c"))
+                .map(|url| url.path().to_owned()),
+            Ok(String::from("/a"))
+        );
+        assert!(<url::Url as ValueParser>::parse(&value("example.com")).is_err());
+        assert!(<url::Url as ValueParser>::parse(&Value::None).is_err());
+        assert_eq!(
+            SchemeUrl::<Http>::parse(&value("http://example.com"))
+                .map(|url| url.into_inner().host_str().map(String::from)),
+            Ok(Some(String::from("example.com")))
+        );
+        assert!(SchemeUrl::<Http>::parse(&value("HTTPS://example.com")).is_ok());
+        assert!(SchemeUrl::<Http>::parse(&value("ftp://example.com")).is_err());
+        assert!(SchemeUrl::<Http>::parse(&value("http//example.com")).is_err());
+    }
+
+    #[test]
+    fn shell_words_test() {
+        use super::*;
+
+        let words = |string: &str| ShellWords::parse(&value(string)).map(Vec::from);
+
+        assert_eq!(
+            words(r#"gcc -O2  "-DNAME=a b" 'x\y' z\ w "" "\"q\"""#),
+            Ok(["gcc", "-O2", "-DNAME=a b", "x\\y", "z w", "", "\"q\""]
+                .iter()
+                .map(|data| String::from(*data))
+                .collect())
+        );
+        assert_eq!(words("  "), Ok(vec![]));
+        assert!(words("'abc").is_err());
+        assert!(words("\"abc").is_err());
+        assert!(words("abc\\").is_err());
+    }
+
+    #[test]
+    fn base64_test() {
+        use super::*;
+
+        let decode = |string: &str| Base64::parse(&value(string)).map(Vec::from);
+
+        assert_eq!(decode("aGVsbG8="), Ok(b"hello".to_vec()));
+        assert_eq!(decode("aGVsbG8"), Ok(b"hello".to_vec()));
+        assert_eq!(decode("aGk-_w=="), Ok(vec![b'h', b'i', 0x3e, 0xff]));
+        assert_eq!(decode(""), Ok(vec![]));
+        assert!(decode("aGVsbG8*").is_err());
+        assert!(decode("aGVsbG8==").is_err());
+        assert!(decode("a").is_err());
+    }
+
+    #[test]
+    fn hex_bytes_test() {
+        use super::*;
+
+        let decode = |string: &str| HexBytes::parse(&value(string)).map(Vec::from);
+
+        assert_eq!(decode("deadBEEF"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(decode("0x00ff"), Ok(vec![0x00, 0xff]));
+        assert_eq!(
+            decode("00-1A-2b-3c-4D-5e"),
+            Ok(vec![0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e])
+        );
+        assert!(decode("00-1A:2b").is_err());
+        assert_eq!(decode("DE:AD:BE:EF"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert!(decode("abc").is_err());
+        assert!(decode("DE:A:BE").is_err());
+        assert!(decode("zz").is_err());
+        assert!(decode("é0").is_err());
+        assert!(decode("").is_err());
+        assert!(decode("0x").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_test() {
+        use super::*;
+
+        assert_eq!(
+            <serde_json::Value as ValueParser>::parse(&value(r#"{"a":1}"#)),
+            Ok(serde_json::json!({ "a": 1 }))
+        );
+        assert_eq!(
+            Json::<Vec<u32>>::parse(&value("[1, 2]")),
+            Ok(Json(vec![1, 2]))
+        );
+        assert!(Json::<Vec<u32>>::parse(&value("[1, -2]")).is_err());
+        assert!(<serde_json::Value as ValueParser>::parse(&value("{")).is_err());
+    }
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn toml_yaml_test() {
+        use super::*;
+        use std::collections::BTreeMap;
+
+        let expect: BTreeMap<String, u32> = [(String::from("a"), 1), (String::from("b"), 2)]
+            .iter()
+            .cloned()
+            .collect();
+
+        assert_eq!(
+            Toml::<BTreeMap<String, u32>>::parse(&value("a = 1\nb = 2")),
+            Ok(Toml(expect.clone()))
+        );
+        assert_eq!(
+            Yaml::<BTreeMap<String, u32>>::parse(&value("{a: 1, b: 2}")),
+            Ok(Yaml(expect))
+        );
+        assert!(Toml::<BTreeMap<String, u32>>::parse(&value("a = ")).is_err());
+        assert!(Yaml::<BTreeMap<String, u32>>::parse(&value("{a: x}")).is_err());
+    }
+
+    #[test]
+    fn csv_test() {
+        use super::*;
+
+        assert_eq!(
+            Csv::<(f64, f64, f64)>::parse(&value("1.0, 2.0,3.0")),
+            Ok(Csv((1.0, 2.0, 3.0)))
+        );
+        assert_eq!(
+            Csv::<Vec<String>>::parse(&value(r#"a, "b, ""c""", d "#)),
+            Ok(Csv(vec![
+                String::from("a"),
+                String::from("b, \"c\""),
+                String::from("d")
+            ]))
+        );
+        assert_eq!(
+            Csv::<(String, u8)>::parse(&value(",1")),
+            Ok(Csv((String::new(), 1)))
+        );
+        assert!(Csv::<(u8, u8)>::parse(&value("1, 2, 3")).is_err());
+        assert!(Csv::<Vec<u8>>::parse(&value("1, x")).is_err());
+        assert!(Csv::<Vec<String>>::parse(&value(r#""a, b"#)).is_err());
+    }
+
+    #[cfg(feature = "glob")]
+    #[test]
+    fn glob_paths_test() {
+        use super::*;
+
+        let paths = GlobPaths::parse(&value("src/*.rs")).unwrap();
+
+        assert!(paths.0.contains(&PathBuf::from("src/val.rs")));
+        assert!(paths.0.iter().all(|path| path.starts_with("src")));
+        assert!(GlobPaths::parse(&value("src/*.nothing")).is_err());
+        assert!(GlobPaths::parse(&value("src/[*.rs")).is_err());
+    }
+
+    #[test]
+    fn pair_test() {
+        use super::*;
+
+        assert_eq!(
+            Pair::<u16, u16, ':'>::parse(&value("8080:80")).map(<(u16, u16)>::from),
+            Ok((8080, 80))
+        );
+        assert_eq!(
+            Pair::<String, String>::parse(&value("a=b=c")),
+            Ok(Pair(String::from("a"), String::from("b=c")))
+        );
+        assert!(Pair::<u16, u16, ':'>::parse(&value("8080")).is_err());
+        assert!(Pair::<u16, u16, ':'>::parse(&value("8080:x")).is_err());
+    }
+
+    #[test]
+    fn bounded_test() {
+        use super::*;
+
+        assert_eq!(
+            Bounded::<u8, 1, 16>::parse(&value("16")).map(Bounded::get),
+            Ok(16)
+        );
+        assert_eq!(Bounded::<i32, -5, 5>::parse(&value("-5")), Ok(Bounded(-5)));
+        assert!(Bounded::<u8, 1, 16>::parse(&value("0")).is_err());
+        assert!(Bounded::<u64, 0, 10>::parse(&value("18446744073709551615")).is_err());
+        assert!(Bounded::<u8, 1, 16>::parse(&value("x")).is_err());
+    }
+
+    #[test]
+    fn default_if_missing_test() {
+        use super::*;
+
+        assert_eq!(
+            DefaultIfMissing::<u32>::parse(&Value::None),
+            Ok(DefaultIfMissing(0))
+        );
+        assert_eq!(
+            DefaultIfMissing::<u32>::parse(&value("7")),
+            Ok(DefaultIfMissing(7))
+        );
+        assert!(DefaultIfMissing::<u32>::parse(&value("")).is_err());
+    }
+
+    #[test]
+    fn parsed_test() {
+        use super::*;
+
+        assert_eq!(
+            Parsed::<std::num::NonZeroU8>::parse(&value("3")).map(|n| n.0.get()),
+            Ok(3)
+        );
+        assert!(Parsed::<std::num::NonZeroU8>::parse(&value("0")).is_err());
+    }
+
+    #[test]
+    fn locale_number_test() {
+        use super::*;
+
+        assert_eq!(
+            LocaleNumber::<f64>::parse(&value("1,000.5")).map(LocaleNumber::get),
+            Ok(1000.5)
+        );
+        assert_eq!(
+            LocaleNumber::<f64, ',', '.'>::parse(&value("-1.000.000,25")),
+            Ok(LocaleNumber(-1000000.25))
+        );
+        assert_eq!(
+            LocaleNumber::<u32>::parse(&value("1_000_000")),
+            Ok(LocaleNumber(1000000))
+        );
+        assert_eq!(
+            LocaleNumber::<f32, ','>::parse(&value("0,5")),
+            Ok(LocaleNumber(0.5))
+        );
+        assert!(LocaleNumber::<u32>::parse(&value("1,,000")).is_err());
+        assert!(LocaleNumber::<u32>::parse(&value(",1")).is_err());
+        assert!(LocaleNumber::<u32>::parse(&value("1.5")).is_err());
+        assert_eq!(
+            LocaleNumber::<f64>::parse(&value("1,5")),
+            Err(String::from("Invalid number `1,5`"))
+        );
+        assert!(LocaleNumber::<f64>::parse(&value("12,34.5")).is_err());
+        assert!(LocaleNumber::<u32>::parse(&value("1,0000")).is_err());
+        assert!(LocaleNumber::<f64, ',', '.'>::parse(&value("1.5")).is_err());
+        assert_eq!(
+            LocaleNumber::<f64>::parse(&value("-12,345.000_5")),
+            Ok(LocaleNumber(-12345.0005))
+        );
+        assert_eq!(
+            LocaleNumber::<u32>::parse(&value("1,000,")),
+            Err(String::from("Invalid number `1,000,`"))
+        );
+    }
+
+    #[cfg(feature = "separators")]
+    #[test]
+    fn separators_test() {
+        use super::*;
+
+        assert_eq!(u64::parse(&value("1_000_000")), Ok(1000000));
+        assert_eq!(i32::parse(&value("-1,000")), Ok(-1000));
+        assert_eq!(
+            u32::parse(&value("1__000")),
+            Err(String::from(
+                "Invalid value `1__000`: misplaced digit separator"
+            ))
+        );
+        assert!(u8::parse(&value("_1")).is_err());
+        assert!(u8::parse(&value("1,000")).is_err());
+        assert_eq!(
+            u32::parse(&value("1,5")),
+            Err(String::from(
+                "Invalid value `1,5`: misplaced digit separator"
+            ))
+        );
+        assert!(u32::parse(&value("12,34")).is_err());
+        assert!(u32::parse(&value("1,0000")).is_err());
+        assert!(u32::parse(&value("1234,567")).is_err());
+        assert!(u32::parse(&value("1,000_000")).is_err());
+        assert_eq!(u32::parse(&value("12,345,678")), Ok(12345678));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn time_zone_test() {
+        use super::*;
+
+        let offset =
+            |seconds: i32| TimeZone::Fixed(chrono::FixedOffset::east_opt(seconds).unwrap());
+
+        assert_eq!(
+            TimeZone::parse(&value("Europe/Berlin")),
+            Ok(TimeZone::Named(chrono_tz::Europe::Berlin))
+        );
+        assert_eq!(TimeZone::parse(&value("+02:00")), Ok(offset(7200)));
+        assert_eq!(TimeZone::parse(&value("-0530")), Ok(offset(-19800)));
+        assert_eq!(TimeZone::parse(&value("+02")), Ok(offset(7200)));
+        assert!(TimeZone::parse(&value("Mars/Olympus")).is_err());
+        assert!(TimeZone::parse(&value("+24:00")).is_err());
+        assert!(TimeZone::parse(&value("+2:00")).is_err());
+        assert!(TimeZone::parse(&value("+1\u{e9}1")).is_err());
+    }
+
+    #[test]
+    fn mime_test() {
+        use super::*;
+
+        let mime = Mime::parse(&value("Text/HTML; Charset=utf-8; title=\"a b\"")).unwrap();
+
+        assert_eq!(mime.essence(), "text/html");
+        assert_eq!(mime.param("charset"), Some("utf-8"));
+        assert_eq!(mime.param("title"), Some("a b"));
+        assert_eq!(mime.to_string(), "text/html; charset=utf-8; title=\"a b\"");
+        assert!(Mime::parse(&value("application/vnd.api+json")).is_ok());
+        assert!(Mime::parse(&value("text")).is_err());
+        assert!(Mime::parse(&value("text/")).is_err());
+        assert!(Mime::parse(&value("text/html; charset")).is_err());
+        assert!(Mime::parse(&value("text/html; title=\"a")).is_err());
+    }
+
+    #[test]
+    fn header_test() {
+        use super::*;
+
+        assert_eq!(
+            Header::parse(&value("Content-Type: text/plain")).map(<(String, String)>::from),
+            Ok((String::from("Content-Type"), String::from("text/plain")))
+        );
+        assert_eq!(
+            Header::parse(&value("Accept=*/*")).map(|header| header.to_string()),
+            Ok(String::from("Accept: */*"))
+        );
+        assert_eq!(
+            Header::parse(&value("X-Empty:")).map(|header| header.value),
+            Ok(String::new())
+        );
+        assert_eq!(
+            Vec::<Header>::parse(&Value::List(vec![
+                String::from("A: 1"),
+                String::from("B: 2")
+            ]))
+            .map(|headers| headers.len()),
+            Ok(2)
+        );
+        assert_eq!(
+            Header::parse(&value("Bad Name: 1")),
+            Err(String::from("Invalid header name `Bad Name`"))
+        );
+        assert!(Header::parse(&value("NoSeparator")).is_err());
+        assert!(Header::parse(&value(": value")).is_err());
+        assert!(Header::parse(&value("A: 1\r\nB: 2")).is_err());
+    }
+
+    #[test]
+    fn host_port_test() {
+        use super::*;
+
+        let host_port = |host: &str, port: u16| (String::from(host), port);
+
+        assert_eq!(
+            HostPort::<0>::parse(&value("example.com:8080")).map(<(String, u16)>::from),
+            Ok(host_port("example.com", 8080))
+        );
+        assert_eq!(
+            HostPort::<443>::parse(&value("example.com")).map(<(String, u16)>::from),
+            Ok(host_port("example.com", 443))
+        );
+        assert_eq!(
+            HostPort::<0>::parse(&value("[::1]:80")).map(<(String, u16)>::from),
+            Ok(host_port("::1", 80))
+        );
+        assert_eq!(
+            HostPort::<22>::parse(&value("fe80::1")).map(|host_port| host_port.to_string()),
+            Ok(String::from("[fe80::1]:22"))
+        );
+        assert_eq!(
+            HostPort::<0>::parse(&value("10.0.0.1:53")).map(<(String, u16)>::from),
+            Ok(host_port("10.0.0.1", 53))
+        );
+        assert_eq!(
+            HostPort::<0>::parse(&value("example.com")),
+            Err(String::from(
+                "Missing port of `example.com`, expect `HOST:PORT`"
+            ))
+        );
+        assert!(HostPort::<0>::parse(&value("example.com:0")).is_err());
+        assert!(HostPort::<0>::parse(&value("example.com:http")).is_err());
+        assert!(HostPort::<80>::parse(&value("-bad.com")).is_err());
+        assert!(HostPort::<80>::parse(&value("[::1")).is_err());
+        assert!(HostPort::<80>::parse(&value("[example.com]:80")).is_err());
+        assert_eq!(
+            HostPort::<0>::parse(&value("999.1.1.1:80")),
+            Err(String::from("Invalid IPv4 address `999.1.1.1`"))
+        );
+        assert!(HostPort::<0>::parse(&value("1.2.3:80")).is_err());
+        assert!(HostPort::<80>::parse(&value("1.2.3.4.")).is_err());
+        assert!(HostPort::<80>::parse(&value("1a.2.3.4")).is_ok());
+        assert_eq!(
+            HostPort::<0>::parse(&value(":80")),
+            Err(String::from("Invalid host name ``"))
+        );
+    }
+
+    #[test]
+    fn email_test() {
+        use super::*;
+
+        assert_eq!(
+            Email::parse(&value("First.Last+tag@Example.COM")).map(|email| email.to_string()),
+            Ok(String::from("First.Last+tag@example.com"))
+        );
+        assert_eq!(
+            Email::parse(&value("user.example.com")),
+            Err(String::from(
+                "Invalid email `user.example.com`: missing `@`"
+            ))
+        );
+        assert_eq!(
+            Email::parse(&value("user@gmail,com")),
+            Err(String::from(
+                "Invalid email `user@gmail,com`: invalid domain after `@`"
+            ))
+        );
+        assert!(Email::parse(&value("a@b@example.com")).is_err());
+        assert!(Email::parse(&value("@example.com")).is_err());
+        assert!(Email::parse(&value("user..name@example.com")).is_err());
+        assert!(Email::parse(&value("user@localhost")).is_err());
+        assert!(Email::parse(&value("us er@example.com")).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Combine the counted `-v` and `-q` switches into one verbosity level.
+//!
+//! ```
+//! use cuteopt::prelude::*;
+//! use cuteopt::verbosity::Verbosity;
+//!
+//! #[derive(Debug, Clone, Eq, PartialEq, Default)]
+//! enum ParseState {
+//!     Verbose,
+//!     Quiet,
+//!     #[default]
+//!     Default,
+//! }
+//!
+//! let mut ctx = Ctx::new();
+//! let verbosity = Verbosity::new(ParseState::Verbose, ParseState::Quiet);
+//!
+//! verbosity.register(&mut ctx);
+//! ctx.set_cluster(true);
+//! ctx.parse(&mut vec!["-vv"].into_iter().map(String::from)).unwrap();
+//!
+//! assert_eq!(verbosity.level(&ctx), 4);
+//! ```
+
+use super::Ctx;
+
+/// Level of `error` messages, `0` means print nothing
+pub const ERROR: u8 = 1;
+
+/// The highest level, same as `trace`
+pub const MAX_LEVEL: u8 = 5;
+
+/// [`Verbosity`] register `-v/--verbose` and `-q/--quiet` with the given states,
+/// every `-v` raise the level and every `-q` lower it,
+/// the level use the same scale as the numeric log levels, `0` is off and `5` is trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verbosity<S> {
+    verbose: S,
+    quiet: S,
+    default: u8,
+}
+
+impl<S> Verbosity<S>
+where
+    S: std::fmt::Debug + Clone + Default + Eq,
+{
+    /// Create a [`Verbosity`] with the default level `warn`
+    pub fn new(verbose: S, quiet: S) -> Self {
+        Verbosity {
+            verbose,
+            quiet,
+            default: ERROR + 1,
+        }
+    }
+
+    /// Set the level used when neither `-v` nor `-q` is given
+    pub fn set_default(&mut self, default: u8) -> &mut Self {
+        self.default = default.min(MAX_LEVEL);
+        self
+    }
+
+    /// Add the `-v/--verbose` and `-q/--quiet` switches to `ctx`
+    pub fn register(&self, ctx: &mut Ctx<'_, S>) {
+        ctx.add_flag("-v", "--verbose", self.verbose.clone())
+            .help(self.verbose.clone(), "Print more messages, can be repeated")
+            .add_flag("-q", "--quiet", self.quiet.clone())
+            .help(self.quiet.clone(), "Print less messages, can be repeated");
+    }
+
+    /// Return the level after applying the counts of `-v` and `-q`, clamped into `0..=5`
+    pub fn level(&self, ctx: &Ctx<'_, S>) -> u8 {
+        let verbose = ctx.get_count(self.verbose.clone());
+        let quiet = ctx.get_count(self.quiet.clone());
+        let level = self.default as isize + verbose as isize - quiet as isize;
+
+        level.clamp(0, MAX_LEVEL as isize) as u8
+    }
+
+    /// Return true if the level is `0`
+    pub fn is_silent(&self, ctx: &Ctx<'_, S>) -> bool {
+        self.level(ctx) == 0
+    }
+
+    /// Map the level into [`log::LevelFilter`]
+    #[cfg(feature = "log")]
+    pub fn level_filter(&self, ctx: &Ctx<'_, S>) -> log::LevelFilter {
+        match self.level(ctx) {
+            0 => log::LevelFilter::Off,
+            1 => log::LevelFilter::Error,
+            2 => log::LevelFilter::Warn,
+            3 => log::LevelFilter::Info,
+            4 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn verbosity_test() {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Default)]
+        enum TestState {
+            Verbose,
+            Quiet,
+            #[default]
+            Unknow,
+        }
+
+        let verbosity = Verbosity::new(TestState::Verbose, TestState::Quiet);
+        let level = |args: &[&str]| {
+            let mut ctx = Ctx::new();
+            let mut args = args.iter().map(|data| String::from(*data));
+
+            verbosity.register(&mut ctx);
+            ctx.set_cluster(true);
+            ctx.parse(&mut args).unwrap();
+            verbosity.level(&ctx)
+        };
+
+        assert_eq!(level(&[]), 2);
+        assert_eq!(level(&["-v", "--verbose"]), 4);
+        assert_eq!(level(&["-vvvvvv"]), MAX_LEVEL);
+        assert_eq!(level(&["-vv", "-q"]), 3);
+        assert_eq!(level(&["-qqq"]), 0);
+    }
+}